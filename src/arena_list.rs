@@ -0,0 +1,810 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+
+// Singly linked list that keeps every node in one Vec instead of boxing each one.
+// Nodes point at each other with u32 indices into that Vec, and removed slots are
+// threaded onto a free list so later pushes reuse them instead of growing the Vec.
+// The public API mirrors linked_list::LinkedList.
+
+// Stands in for a null pointer, since index 0 is a real slot
+const NIL: u32 = u32::MAX;
+
+enum Slot<T> {
+    Occupied { data: T, next: u32 },
+    Vacant { next_free: u32 },
+}
+
+pub struct ArenaList<T> {
+    slots: Vec<Slot<T>>,
+    head: u32,
+    // first vacant slot, or NIL when every slot is in use
+    free: u32,
+    len: usize,
+}
+
+impl<T> ArenaList<T> {
+    pub fn new() -> Self {
+        ArenaList {
+            slots: Vec::new(),
+            head: NIL,
+            free: NIL,
+            len: 0,
+        }
+    }
+
+    // Preallocates room for `capacity` nodes so the Vec doesn't have to grow
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaList {
+            slots: Vec::with_capacity(capacity),
+            ..Self::new()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head == NIL
+    }
+
+    // Drops every node but keeps the Vec's allocation around for reuse
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.head = NIL;
+        self.free = NIL;
+        self.len = 0;
+    }
+
+    pub fn push(&mut self, data: T) {
+        self.head = self.alloc(data, self.head);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.head == NIL {
+            return None;
+        }
+
+        let old_head = self.head;
+        self.head = self.next_of(old_head);
+        self.len -= 1;
+        Some(self.release(old_head))
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.get_slot(self.head)
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        let head = self.head;
+        self.get_slot_mut(head)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            slots: &self.slots,
+            next: self.head,
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            slots: self.slots.as_mut_ptr(),
+            next: self.head,
+            len: self.len,
+            _borrow: PhantomData,
+        }
+    }
+
+    pub fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().position(predicate)
+    }
+
+    pub fn find_map<B, F>(&self, f: F) -> Option<B>
+    where
+        F: FnMut(&T) -> Option<B>,
+    {
+        self.iter().find_map(f)
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        self.iter().nth(idx)
+    }
+
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.iter_mut().nth(idx)
+    }
+
+    // Panics if idx is greater than the length of the list, like Vec::insert
+    pub fn insert(&mut self, idx: usize, data: T) {
+        assert!(idx <= self.len, "insert index {} out of bounds (len {})", idx, self.len);
+
+        if idx == 0 {
+            self.push(data);
+            return;
+        }
+
+        let prev = self.index_at(idx - 1);
+        let node = self.alloc(data, self.next_of(prev));
+        self.set_next(prev, node);
+        self.len += 1;
+    }
+
+    pub fn remove(&mut self, idx: usize) -> Option<T> {
+        if idx >= self.len {
+            return None;
+        }
+        if idx == 0 {
+            return self.pop();
+        }
+
+        let prev = self.index_at(idx - 1);
+        let removed = self.next_of(prev);
+        self.set_next(prev, self.next_of(removed));
+        self.len -= 1;
+        Some(self.release(removed))
+    }
+
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.extract_if(|data| !keep(data)).for_each(drop);
+    }
+
+    pub fn extract_if<F>(&mut self, predicate: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf {
+            curr: self.head,
+            prev: NIL,
+            list: self,
+            predicate,
+        }
+    }
+
+    // Reverses the list by flipping the next index of each node
+    pub fn reverse(&mut self) {
+        let mut prev = NIL;
+        let mut curr = self.head;
+
+        while curr != NIL {
+            let next = self.next_of(curr);
+            self.set_next(curr, prev);
+            prev = curr;
+            curr = next;
+        }
+
+        self.head = prev;
+    }
+
+    // Self keeps the nodes before `at` and the returned list gets the rest.
+    // The moved nodes are copied into the new list's own arena.
+    // Panics if `at` is greater than the length of the list
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "split index {} out of bounds (len {})", at, self.len);
+
+        let mut curr = if at == 0 {
+            std::mem::replace(&mut self.head, NIL)
+        } else {
+            let last_kept = self.index_at(at - 1);
+            let first_moved = self.next_of(last_kept);
+            self.set_next(last_kept, NIL);
+            first_moved
+        };
+
+        let mut tail = ArenaList::with_capacity(self.len - at);
+        let mut tail_end = NIL;
+        while curr != NIL {
+            let next = self.next_of(curr);
+            let node = tail.alloc(self.release(curr), NIL);
+            if tail_end == NIL {
+                tail.head = node;
+            } else {
+                tail.set_next(tail_end, node);
+            }
+            tail_end = node;
+            curr = next;
+        }
+
+        tail.len = self.len - at;
+        self.len = at;
+        tail
+    }
+
+    pub fn split_at(mut self, at: usize) -> (Self, Self) {
+        let tail = self.split_off(at);
+        (self, tail)
+    }
+
+    // For an even number of nodes this returns the second of the two middles
+    pub fn middle(&self) -> Option<&T> {
+        self.get(self.len / 2)
+    }
+
+    // n = 1 is the last node
+    pub fn remove_nth_from_end(&mut self, n: usize) -> Option<T> {
+        if n == 0 || n > self.len {
+            return None;
+        }
+
+        self.remove(self.len - n)
+    }
+
+    // Stable sort. The data never moves; only the next indices are rewritten
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut order: Vec<u32> = Vec::with_capacity(self.len);
+        let mut curr = self.head;
+        while curr != NIL {
+            order.push(curr);
+            curr = self.next_of(curr);
+        }
+
+        order.sort_by(|&a, &b| compare(self.data_of(a), self.data_of(b)));
+
+        // relink the nodes in sorted order
+        self.head = order.first().copied().unwrap_or(NIL);
+        for pair in order.windows(2) {
+            self.set_next(pair[0], pair[1]);
+        }
+        if let Some(&last) = order.last() {
+            self.set_next(last, NIL);
+        }
+    }
+
+    // Takes a slot off the free list, or grows the Vec if there isn't one
+    fn alloc(&mut self, data: T, next: u32) -> u32 {
+        let slot = Slot::Occupied { data, next };
+
+        if self.free != NIL {
+            let idx = self.free;
+            self.free = match self.slots[idx as usize] {
+                Slot::Vacant { next_free } => next_free,
+                Slot::Occupied { .. } => unreachable!("free list points at an occupied slot"),
+            };
+            self.slots[idx as usize] = slot;
+            return idx;
+        }
+
+        // NIL is reserved, so the last usable index is u32::MAX - 1
+        assert!(self.slots.len() < NIL as usize, "ArenaList is limited to u32::MAX - 1 nodes");
+        self.slots.push(slot);
+        (self.slots.len() - 1) as u32
+    }
+
+    // Moves the data out of a slot and puts the slot on the free list
+    fn release(&mut self, idx: u32) -> T {
+        let vacant = Slot::Vacant {
+            next_free: self.free,
+        };
+        self.free = idx;
+
+        match std::mem::replace(&mut self.slots[idx as usize], vacant) {
+            Slot::Occupied { data, .. } => data,
+            Slot::Vacant { .. } => unreachable!("released a vacant slot"),
+        }
+    }
+
+    fn get_slot(&self, idx: u32) -> Option<&T> {
+        match self.slots.get(idx as usize) {
+            Some(Slot::Occupied { data, .. }) => Some(data),
+            _ => None,
+        }
+    }
+
+    fn get_slot_mut(&mut self, idx: u32) -> Option<&mut T> {
+        match self.slots.get_mut(idx as usize) {
+            Some(Slot::Occupied { data, .. }) => Some(data),
+            _ => None,
+        }
+    }
+
+    fn data_of(&self, idx: u32) -> &T {
+        self.get_slot(idx).expect("index does not point at a node")
+    }
+
+    fn next_of(&self, idx: u32) -> u32 {
+        match self.slots[idx as usize] {
+            Slot::Occupied { next, .. } => next,
+            Slot::Vacant { .. } => unreachable!("followed a link into a vacant slot"),
+        }
+    }
+
+    fn set_next(&mut self, idx: u32, new_next: u32) {
+        match &mut self.slots[idx as usize] {
+            Slot::Occupied { next, .. } => *next = new_next,
+            Slot::Vacant { .. } => unreachable!("linked a vacant slot"),
+        }
+    }
+
+    // Arena index of the node at list position idx. Caller checks idx < len
+    fn index_at(&self, idx: usize) -> u32 {
+        let mut curr = self.head;
+        for _ in 0..idx {
+            curr = self.next_of(curr);
+        }
+        curr
+    }
+}
+
+impl<T: PartialEq> ArenaList<T> {
+    pub fn find(&self, target: &T) -> Option<usize> {
+        self.position(|data| data == target)
+    }
+
+    pub fn contains(&self, target: &T) -> bool {
+        self.iter().any(|data| data == target)
+    }
+
+    pub fn remove_first(&mut self, target: &T) -> Option<T> {
+        let idx = self.find(target)?;
+        self.remove(idx)
+    }
+
+    // Removes consecutive repeated elements
+    pub fn dedup(&mut self) {
+        let mut curr = self.head;
+        while curr != NIL {
+            let mut next = self.next_of(curr);
+            while next != NIL && self.data_of(next) == self.data_of(curr) {
+                let after = self.next_of(next);
+                self.set_next(curr, after);
+                self.release(next);
+                self.len -= 1;
+                next = after;
+            }
+            curr = next;
+        }
+    }
+}
+
+impl<T: Ord> ArenaList<T> {
+    pub fn sort(&mut self) {
+        self.sort_by(|a, b| a.cmp(b));
+    }
+
+    // Merges another sorted list into this one. Other's nodes are moved into
+    // this arena first, then the two chains are merged by relinking indices
+    pub fn merge_sorted(&mut self, mut other: Self) {
+        let mut right = NIL;
+        let mut right_end = NIL;
+        while let Some(data) = other.pop() {
+            let node = self.alloc(data, NIL);
+            if right_end == NIL {
+                right = node;
+            } else {
+                self.set_next(right_end, node);
+            }
+            right_end = node;
+            self.len += 1;
+        }
+
+        let mut left = self.head;
+        let mut merged_end = NIL;
+        self.head = NIL;
+        while left != NIL || right != NIL {
+            // on ties take from the left chain to keep the merge stable
+            let take_right =
+                left == NIL || (right != NIL && self.data_of(right) < self.data_of(left));
+            let node = if take_right {
+                let node = right;
+                right = self.next_of(right);
+                node
+            } else {
+                let node = left;
+                left = self.next_of(left);
+                node
+            };
+
+            if merged_end == NIL {
+                self.head = node;
+            } else {
+                self.set_next(merged_end, node);
+            }
+            merged_end = node;
+        }
+        if merged_end != NIL {
+            self.set_next(merged_end, NIL);
+        }
+    }
+}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The first item yielded ends up at the head of the list
+impl<T> FromIterator<T> for ArenaList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = ArenaList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+// Appends to the tail of the list, same as LinkedList
+impl<T> Extend<T> for ArenaList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut tail = self.head;
+        if tail != NIL {
+            while self.next_of(tail) != NIL {
+                tail = self.next_of(tail);
+            }
+        }
+
+        let iter = iter.into_iter();
+        self.slots.reserve(iter.size_hint().0);
+        for data in iter {
+            let node = self.alloc(data, NIL);
+            if tail == NIL {
+                self.head = node;
+            } else {
+                self.set_next(tail, node);
+            }
+            tail = node;
+            self.len += 1;
+        }
+    }
+}
+
+impl<'a, T: 'a + Copy> Extend<&'a T> for ArenaList<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+// Cloning compacts the nodes into a fresh arena in list order
+impl<T: Clone> Clone for ArenaList<T> {
+    fn clone(&self) -> Self {
+        let mut list = ArenaList::with_capacity(self.len);
+        list.extend(self.iter().cloned());
+        list
+    }
+}
+
+impl<T: Debug> Debug for ArenaList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for ArenaList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for ArenaList<T> {}
+
+impl<T: PartialOrd> PartialOrd for ArenaList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for ArenaList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for ArenaList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for data in self.iter() {
+            data.hash(state);
+        }
+    }
+}
+
+pub struct IntoIter<T>(ArenaList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for ArenaList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+pub struct Iter<'a, T> {
+    slots: &'a [Slot<T>],
+    next: u32,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.slots.get(self.next as usize) {
+            Some(Slot::Occupied { data, next }) => {
+                self.next = *next;
+                self.len -= 1;
+                Some(data)
+            }
+            _ => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a ArenaList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+// Hands out &mut to several slots of the same Vec, which the borrow checker
+// can't see is fine, so this walks a raw pointer to the start of the Vec
+pub struct IterMut<'a, T> {
+    slots: *mut Slot<T>,
+    next: u32,
+    len: usize,
+    _borrow: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // SAFETY: the list is acyclic and holds len nodes, so the first len indices
+        // reached from the head are distinct, in bounds and occupied. Each slot is
+        // therefore handed out at most once while the list is mutably borrowed.
+        let slot = unsafe { &mut *self.slots.add(self.next as usize) };
+        match slot {
+            Slot::Occupied { data, next } => {
+                self.next = *next;
+                self.len -= 1;
+                Some(data)
+            }
+            Slot::Vacant { .. } => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a mut ArenaList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+pub struct ExtractIf<'a, T, F> {
+    list: &'a mut ArenaList<T>,
+    // node the iterator looks at next, and the node before it (NIL at the head)
+    curr: u32,
+    prev: u32,
+    predicate: F,
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.curr != NIL {
+            let curr = self.curr;
+            let next = self.list.next_of(curr);
+            self.curr = next;
+
+            let matched = match self.list.get_slot_mut(curr) {
+                Some(data) => (self.predicate)(data),
+                None => false,
+            };
+
+            if matched {
+                if self.prev == NIL {
+                    self.list.head = next;
+                } else {
+                    self.list.set_next(self.prev, next);
+                }
+                self.list.len -= 1;
+                return Some(self.list.release(curr));
+            }
+
+            self.prev = curr;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linked_list::LinkedList;
+
+    fn list_of(items: &[i32]) -> ArenaList<i32> {
+        items.iter().copied().collect()
+    }
+
+    fn to_vec(list: &ArenaList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_ll_push() {
+        let mut list = ArenaList::new();
+        list.push(1);
+        list.push(2);
+        list.push(3);
+
+        assert_eq!(list.pop(), Some(3));
+        assert_eq!(list.pop(), Some(2));
+
+        list.push(5);
+        list.push(6);
+
+        assert_eq!(list.pop(), Some(6));
+        assert_eq!(list.pop(), Some(5));
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.pop(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_free_list_reuse() {
+        let mut list = ArenaList::new();
+        for i in 0..4 {
+            list.push(i);
+        }
+        list.pop();
+        list.pop();
+        assert_eq!(list.slots.len(), 4);
+
+        // the two freed slots get reused before the Vec grows again
+        list.push(10);
+        list.push(11);
+        assert_eq!(list.slots.len(), 4);
+        list.push(12);
+        assert_eq!(list.slots.len(), 5);
+        assert_eq!(to_vec(&list), vec![12, 11, 10, 1, 0]);
+        assert_eq!(list.len(), 5);
+    }
+
+    #[test]
+    fn test_peek_and_iters() {
+        let mut list = list_of(&[1, 3, 2]);
+        assert_eq!(list.peek(), Some(&1));
+        if let Some(val) = list.peek_mut() {
+            *val = 42;
+        }
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&42));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), None);
+
+        for val in &mut list {
+            *val += 1;
+        }
+        assert_eq!(to_vec(&list), vec![43, 4, 3]);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![43, 4, 3]);
+    }
+
+    #[test]
+    fn test_search_and_edit() {
+        let mut list = list_of(&[1, 3]);
+        list.insert(1, 2);
+        list.insert(3, 4);
+        list.insert(0, 0);
+        assert_eq!(to_vec(&list), vec![0, 1, 2, 3, 4]);
+
+        assert_eq!(list.find(&3), Some(3));
+        assert!(list.contains(&4));
+        assert_eq!(list.position(|x| *x > 1), Some(2));
+        assert_eq!(list.find_map(|x| if *x == 2 { Some("two") } else { None }), Some("two"));
+        assert_eq!(list.get(4), Some(&4));
+        assert_eq!(list.middle(), Some(&2));
+
+        if let Some(val) = list.get_mut(1) {
+            *val = 10;
+        }
+        assert_eq!(list.remove(1), Some(10));
+        assert_eq!(list.remove(9), None);
+        assert_eq!(list.remove_first(&3), Some(3));
+        assert_eq!(list.remove_nth_from_end(1), Some(4));
+        assert_eq!(to_vec(&list), vec![0, 2]);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn test_retain_and_extract_if() {
+        let mut list = list_of(&[1, 2, 3, 4, 5, 6]);
+        list.retain(|x| x % 3 != 0);
+        assert_eq!(to_vec(&list), vec![1, 2, 4, 5]);
+
+        let evens: Vec<i32> = list.extract_if(|x| *x % 2 == 0).collect();
+        assert_eq!(evens, vec![2, 4]);
+        assert_eq!(to_vec(&list), vec![1, 5]);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn test_reorder() {
+        let mut list = list_of(&[3, 1, 2, 2]);
+        list.reverse();
+        assert_eq!(to_vec(&list), vec![2, 2, 1, 3]);
+
+        list.sort();
+        assert_eq!(to_vec(&list), vec![1, 2, 2, 3]);
+        list.dedup();
+        assert_eq!(to_vec(&list), vec![1, 2, 3]);
+
+        list.merge_sorted(list_of(&[0, 2, 5]));
+        assert_eq!(to_vec(&list), vec![0, 1, 2, 2, 3, 5]);
+        assert_eq!(list.len(), 6);
+
+        let tail = list.split_off(4);
+        assert_eq!(to_vec(&list), vec![0, 1, 2, 2]);
+        assert_eq!(to_vec(&tail), vec![3, 5]);
+
+        let (left, right) = list.split_at(0);
+        assert!(left.is_empty());
+        assert_eq!(right.len(), 4);
+    }
+
+    #[test]
+    fn test_matches_linked_list() {
+        let values = [5, 3, 9, 1, 7];
+        let boxed: LinkedList<i32> = values.iter().copied().collect();
+        let arena: ArenaList<i32> = values.iter().copied().collect();
+
+        assert_eq!(format!("{:?}", boxed), format!("{:?}", arena));
+        assert!(boxed.iter().eq(arena.iter()));
+
+        let cloned = arena.clone();
+        assert_eq!(cloned, arena);
+        assert!(list_of(&[5, 3]) < arena);
+        assert_eq!(ArenaList::<i32>::default(), ArenaList::new());
+    }
+}
@@ -0,0 +1,106 @@
+// Compares the Box based LinkedList against the Vec backed ArenaList.
+// Run with `cargo run --release --bin list_bench [node count]`
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use solved_problems::arena_list::ArenaList;
+use solved_problems::linked_list::LinkedList;
+
+const ROUNDS: u32 = 5;
+
+// how many nodes the churn benchmark keeps in the list
+const CHURN_DEPTH: usize = 64;
+
+// Runs the closure a few times and keeps the fastest run to cut down on noise
+fn time_best<F: FnMut()>(mut f: F) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, ops: usize, boxed: Duration, arena: Duration) {
+    let per_sec = |elapsed: Duration| ops as f64 / elapsed.as_secs_f64() / 1_000_000.0;
+    println!(
+        "{:<12} box: {:>8.2} Mops/s   arena: {:>8.2} Mops/s   speedup: {:.2}x",
+        name,
+        per_sec(boxed),
+        per_sec(arena),
+        boxed.as_secs_f64() / arena.as_secs_f64()
+    );
+}
+
+fn main() {
+    let count: usize = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(1_000_000);
+
+    println!("{} nodes, best of {} rounds", count, ROUNDS);
+
+    // push every node, then drop the list
+    let boxed = time_best(|| {
+        let mut list = LinkedList::new();
+        for i in 0..count {
+            list.push(i);
+        }
+        black_box(&list);
+    });
+    let arena = time_best(|| {
+        let mut list = ArenaList::new();
+        for i in 0..count {
+            list.push(i);
+        }
+        black_box(&list);
+    });
+    report("push", count, boxed, arena);
+
+    // pop every node off a full list
+    let boxed = time_best(|| {
+        let mut list: LinkedList<usize> = (0..count).collect();
+        while let Some(val) = list.pop() {
+            black_box(val);
+        }
+    });
+    let arena = time_best(|| {
+        let mut list: ArenaList<usize> = (0..count).collect();
+        while let Some(val) = list.pop() {
+            black_box(val);
+        }
+    });
+    report("fill + pop", count * 2, boxed, arena);
+
+    // walk the whole list
+    let boxed_list: LinkedList<usize> = (0..count).collect();
+    let arena_list: ArenaList<usize> = (0..count).collect();
+    let boxed = time_best(|| {
+        black_box(boxed_list.iter().sum::<usize>());
+    });
+    let arena = time_best(|| {
+        black_box(arena_list.iter().sum::<usize>());
+    });
+    report("iterate", count, boxed, arena);
+
+    // work queue pattern: keep the list short and churn through nodes, which is
+    // where reusing freed arena slots pays off. Each round pushes one node and
+    // pops one, so after the warm-up fill the list stays at CHURN_DEPTH nodes
+    let boxed = time_best(|| {
+        let mut list: LinkedList<usize> = (0..CHURN_DEPTH).collect();
+        for i in 0..count {
+            list.push(i);
+            black_box(list.pop());
+        }
+    });
+    let arena = time_best(|| {
+        let mut list: ArenaList<usize> = (0..CHURN_DEPTH).collect();
+        for i in 0..count {
+            list.push(i);
+            black_box(list.pop());
+        }
+    });
+    report("churn", count * 2, boxed, arena);
+}
@@ -0,0 +1,265 @@
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+use crossbeam_epoch::{self as epoch, Atomic, Owned};
+
+// Same push / pop at the head model as LinkedList, but the head is an atomic
+// pointer that threads swing with compare-and-swap instead of a Box behind &mut.
+// That makes it a lock free Treiber stack.
+//
+// A popped node can't be freed straight away because another thread may have
+// loaded the same head and still be reading its next pointer. crossbeam's epoch
+// based reclamation defers the free until every thread that could still see the
+// node has moved on.
+pub struct ConcurrentStack<T> {
+    head: Atomic<Node<T>>,
+}
+
+// The data is moved out with ptr::read when a node is popped, so the deferred
+// destructor must not drop it a second time
+struct Node<T> {
+    data: ManuallyDrop<T>,
+    next: Atomic<Node<T>>,
+}
+
+// Values only ever move in and out of the stack whole and no &T is handed out,
+// so sharing the stack between threads just needs T to be sendable
+unsafe impl<T: Send> Send for ConcurrentStack<T> {}
+unsafe impl<T: Send> Sync for ConcurrentStack<T> {}
+
+impl<T> ConcurrentStack<T> {
+    pub fn new() -> Self {
+        ConcurrentStack {
+            head: Atomic::null(),
+        }
+    }
+
+    pub fn push(&self, data: T) {
+        let mut new_node = Owned::new(Node {
+            data: ManuallyDrop::new(data),
+            next: Atomic::null(),
+        });
+
+        let guard = epoch::pin();
+        loop {
+            // point the new node at the current head, then try to swap it in.
+            // If another thread changed the head first, retry with the new head
+            let head = self.head.load(Relaxed, &guard);
+            new_node.next.store(head, Relaxed);
+
+            match self
+                .head
+                .compare_exchange(head, new_node, Release, Relaxed, &guard)
+            {
+                Ok(_) => return,
+                Err(err) => new_node = err.new,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Acquire, &guard);
+            // SAFETY: the guard keeps head from being freed while we look at it
+            let node = unsafe { head.as_ref() }?;
+            let next = node.next.load(Relaxed, &guard);
+
+            if self
+                .head
+                .compare_exchange(head, next, Relaxed, Relaxed, &guard)
+                .is_ok()
+            {
+                // SAFETY: the successful swap means this thread alone unlinked the
+                // node, so only we take its data and schedule it to be freed
+                unsafe {
+                    guard.defer_destroy(head);
+                    return Some(ManuallyDrop::into_inner(ptr::read(&node.data)));
+                }
+            }
+        }
+    }
+
+    // Only a snapshot: other threads may push or pop right after this returns
+    pub fn is_empty(&self) -> bool {
+        let guard = epoch::pin();
+        self.head.load(Acquire, &guard).is_null()
+    }
+}
+
+impl<T> Default for ConcurrentStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Having &mut self means no other thread can touch the stack, so the nodes
+// can be freed directly, walking the chain iteratively like LinkedList's Drop
+impl<T> Drop for ConcurrentStack<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let guard = epoch::unprotected();
+            let mut curr = self.head.load(Relaxed, guard);
+            while !curr.is_null() {
+                let mut owned = curr.into_owned();
+                curr = owned.next.load(Relaxed, guard);
+                ManuallyDrop::drop(&mut owned.data);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_push_pop() {
+        assert_send_sync::<ConcurrentStack<Vec<u8>>>();
+
+        let stack = ConcurrentStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert!(!stack.is_empty());
+
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+        stack.push(5);
+        assert_eq!(stack.pop(), Some(5));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn test_concurrent_push_then_pop() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 5_000;
+
+        let stack = Arc::new(ConcurrentStack::new());
+
+        let pushers: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                    }
+                })
+            })
+            .collect();
+        for handle in pushers {
+            handle.join().unwrap();
+        }
+
+        let poppers: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    while let Some(val) = stack.pop() {
+                        popped.push(val);
+                    }
+                    popped
+                })
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        for handle in poppers {
+            for val in handle.join().unwrap() {
+                // a value coming out twice would mean a node was popped twice
+                assert!(seen.insert(val), "value {} popped twice", val);
+            }
+        }
+        assert_eq!(seen.len(), THREADS * PER_THREAD);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_mixed_push_pop() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 10_000;
+
+        let stack = Arc::new(ConcurrentStack::new());
+        let barrier = Arc::new(Barrier::new(THREADS));
+
+        // every thread interleaves its pushes with pops so the head is always contended
+        let workers: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = Arc::clone(&stack);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut popped = Vec::new();
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                        if i % 2 == 0 {
+                            if let Some(val) = stack.pop() {
+                                popped.push(val);
+                            }
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+
+        let mut all: Vec<usize> = Vec::new();
+        for handle in workers {
+            all.extend(handle.join().unwrap());
+        }
+        while let Some(val) = stack.pop() {
+            all.push(val);
+        }
+
+        // everything pushed comes back out exactly once
+        all.sort_unstable();
+        assert_eq!(all, (0..THREADS * PER_THREAD).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_values_dropped_once() {
+        struct Tracked(Arc<AtomicUsize>);
+
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Relaxed);
+            }
+        }
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        {
+            let stack = ConcurrentStack::new();
+            for _ in 0..100 {
+                stack.push(Tracked(Arc::clone(&drops)));
+            }
+            for _ in 0..40 {
+                drop(stack.pop());
+            }
+            assert_eq!(drops.load(Relaxed), 40);
+        }
+        // the remaining 60 are dropped with the stack, and none of the popped
+        // values are dropped again when their nodes are reclaimed
+        assert_eq!(drops.load(Relaxed), 100);
+    }
+
+    #[test]
+    fn test_long_stack_drop() {
+        let stack = ConcurrentStack::new();
+        for i in 0..200_000 {
+            stack.push(i);
+        }
+        drop(stack);
+    }
+}
@@ -0,0 +1,1043 @@
+use std::fmt::{self, Debug};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+// Each node points both ways, so ownership can't be expressed with Box alone.
+// Nodes are allocated with Box and then leaked into raw pointers; the list is
+// responsible for turning them back into Boxes when they are removed.
+struct Node<T> {
+    data: T,
+    prev: Link<T>,
+    next: Link<T>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+// Refers to one node of a DList so it can be read, moved or removed later in O(1)
+// without walking the list to find it again (lru_cache keeps one per key). A handle
+// is only a pointer: the list doesn't keep track of the handles it has given out,
+// so the methods taking one are unsafe and the caller has to make sure the node is
+// still in that same list.
+pub(crate) struct NodeHandle<T>(NonNull<Node<T>>);
+
+impl<T> Clone for NodeHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NodeHandle<T> {}
+
+impl<T> PartialEq for NodeHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Eq for NodeHandle<T> {}
+
+// Doubly linked list that keeps pointers to both ends for O(1) push / pop at either side
+pub struct DList<T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    // tells the compiler we own values of type T (matters for drop check)
+    _owns: PhantomData<T>,
+}
+
+// The raw pointers opt us out of the auto traits, but the list owns its nodes
+// outright, so it is as thread safe as a Vec<T> would be
+unsafe impl<T: Send> Send for DList<T> {}
+unsafe impl<T: Sync> Sync for DList<T> {}
+
+impl<T> DList<T> {
+    pub fn new() -> Self {
+        DList {
+            front: None,
+            back: None,
+            len: 0,
+            _owns: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn push_front(&mut self, data: T) {
+        let new_node = Self::alloc(data);
+        // SAFETY: new_node was just allocated and isn't linked anywhere yet
+        unsafe { self.link_front(new_node) };
+    }
+
+    pub fn push_back(&mut self, data: T) {
+        let new_node = Self::alloc(data);
+        // SAFETY: same as push_front, mirrored
+        unsafe {
+            match self.back {
+                Some(old) => {
+                    (*old.as_ptr()).next = Some(new_node);
+                    (*new_node.as_ptr()).prev = Some(old);
+                }
+                None => self.front = Some(new_node),
+            }
+        }
+        self.back = Some(new_node);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.front.map(|node| {
+            // SAFETY: node is the current front and is owned by self; we unlink it
+            // before turning it back into a Box so nothing else points at it
+            unsafe {
+                let boxed = Box::from_raw(node.as_ptr());
+                self.front = boxed.next;
+                match self.front {
+                    Some(new_front) => (*new_front.as_ptr()).prev = None,
+                    None => self.back = None,
+                }
+                self.len -= 1;
+                boxed.data
+            }
+        })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.back.map(|node| {
+            // SAFETY: same as pop_front, mirrored
+            unsafe {
+                let boxed = Box::from_raw(node.as_ptr());
+                self.back = boxed.prev;
+                match self.back {
+                    Some(new_back) => (*new_back.as_ptr()).next = None,
+                    None => self.front = None,
+                }
+                self.len -= 1;
+                boxed.data
+            }
+        })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        // SAFETY: the node lives as long as the shared borrow of self
+        self.front.map(|node| unsafe { &(*node.as_ptr()).data })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.front.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.back.map(|node| unsafe { &(*node.as_ptr()).data })
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.back.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    // Moves every node of other onto the back of self in O(1)
+    pub fn append(&mut self, other: &mut Self) {
+        match (self.back, other.front) {
+            (_, None) => {}
+            (None, Some(_)) => std::mem::swap(self, other),
+            (Some(back), Some(other_front)) => {
+                // SAFETY: both nodes are owned by their lists, and other is emptied
+                // below so the nodes end up owned by self only
+                unsafe {
+                    (*back.as_ptr()).next = Some(other_front);
+                    (*other_front.as_ptr()).prev = Some(back);
+                }
+                self.back = other.back.take();
+                other.front = None;
+                self.len += std::mem::replace(&mut other.len, 0);
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.front,
+            back: self.back,
+            len: self.len,
+            _borrow: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.front,
+            back: self.back,
+            len: self.len,
+            _borrow: PhantomData,
+        }
+    }
+
+    // Read-only cursor that starts on the front element (or the ghost if empty)
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            current: self.front,
+            index: 0,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            current: self.back,
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    // Mutable cursor that can edit the list around its position
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.front,
+            index: 0,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.back,
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    // push_front, handing back a handle to the new node
+    pub(crate) fn push_front_handle(&mut self, data: T) -> NodeHandle<T> {
+        let new_node = Self::alloc(data);
+        // SAFETY: new_node was just allocated and isn't linked anywhere yet
+        unsafe { self.link_front(new_node) };
+        NodeHandle(new_node)
+    }
+
+    pub(crate) fn back_handle(&self) -> Option<NodeHandle<T>> {
+        self.back.map(NodeHandle)
+    }
+
+    // SAFETY (for all the *_by_handle methods): handle must point at a node that
+    // is currently in self, i.e. it came from this list and hasn't been removed
+    pub(crate) unsafe fn get_by_handle(&self, handle: NodeHandle<T>) -> &T {
+        &(*handle.0.as_ptr()).data
+    }
+
+    pub(crate) unsafe fn get_mut_by_handle(&mut self, handle: NodeHandle<T>) -> &mut T {
+        &mut (*handle.0.as_ptr()).data
+    }
+
+    pub(crate) unsafe fn remove_by_handle(&mut self, handle: NodeHandle<T>) -> T {
+        self.unlink(handle.0);
+        Box::from_raw(handle.0.as_ptr()).data
+    }
+
+    pub(crate) unsafe fn move_to_front_by_handle(&mut self, handle: NodeHandle<T>) {
+        if self.front != Some(handle.0) {
+            self.unlink(handle.0);
+            self.link_front(handle.0);
+        }
+    }
+
+    // SAFETY: node must be owned by self and not currently linked into it
+    unsafe fn link_front(&mut self, node: NonNull<Node<T>>) {
+        (*node.as_ptr()).prev = None;
+        (*node.as_ptr()).next = self.front;
+        match self.front {
+            Some(old) => (*old.as_ptr()).prev = Some(node),
+            None => self.back = Some(node),
+        }
+        self.front = Some(node);
+        self.len += 1;
+    }
+
+    // Joins the neighbours of node to each other. Node keeps its stale links and
+    // still has to be freed or relinked by the caller.
+    // SAFETY: node must currently be in self
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) {
+        let (prev, next) = ((*node.as_ptr()).prev, (*node.as_ptr()).next);
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = next,
+            None => self.front = next,
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = prev,
+            None => self.back = prev,
+        }
+        self.len -= 1;
+    }
+
+    fn alloc(data: T) -> NonNull<Node<T>> {
+        let boxed = Box::new(Node {
+            data,
+            prev: None,
+            next: None,
+        });
+        NonNull::from(Box::leak(boxed))
+    }
+}
+
+impl<T: PartialEq> DList<T> {
+    pub fn contains(&self, target: &T) -> bool {
+        self.iter().any(|data| data == target)
+    }
+}
+
+impl<T> Drop for DList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for DList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for DList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug> Debug for DList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for DList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for DList<T> {}
+
+impl<T> FromIterator<T> for DList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = DList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for DList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for data in iter {
+            self.push_back(data);
+        }
+    }
+}
+
+pub struct IntoIter<T>(DList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for DList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+// The iterators walk inwards from both ends and use len to know when the two
+// ends have met, so a node is never yielded twice
+pub struct Iter<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _borrow: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.front.map(|node| {
+            // SAFETY: the node is kept alive by the shared borrow of the list
+            let node = unsafe { &*node.as_ptr() };
+            self.len -= 1;
+            self.front = node.next;
+            &node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.back.map(|node| {
+            let node = unsafe { &*node.as_ptr() };
+            self.len -= 1;
+            self.back = node.prev;
+            &node.data
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a DList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+pub struct IterMut<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _borrow: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.front.map(|node| {
+            // SAFETY: each node is handed out at most once thanks to len, so the
+            // mutable references never alias
+            let node = unsafe { &mut *node.as_ptr() };
+            self.len -= 1;
+            self.front = node.next;
+            &mut node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.back.map(|node| {
+            let node = unsafe { &mut *node.as_ptr() };
+            self.len -= 1;
+            self.back = node.prev;
+            &mut node.data
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a mut DList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+// A cursor sits on one element of the list, or on the "ghost" position between
+// the back and the front. Moving past either end lands on the ghost, and moving
+// again wraps around to the other end. The ghost's index is the list's length.
+pub struct Cursor<'a, T> {
+    current: Link<T>,
+    index: usize,
+    list: &'a DList<T>,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    // Index of the current element, or None on the ghost
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            // SAFETY: current always points at a node owned by the borrowed list
+            Some(node) => unsafe {
+                self.current = (*node.as_ptr()).next;
+                self.index += 1;
+            },
+            None => {
+                self.current = self.list.front;
+                self.index = 0;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(node) => unsafe {
+                self.current = (*node.as_ptr()).prev;
+                // stepping off the front lands on the ghost, whose index is len
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            },
+            None => {
+                self.current = self.list.back;
+                self.index = self.list.len.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        self.current.map(|node| unsafe { &(*node.as_ptr()).data })
+    }
+
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.front,
+        };
+        next.map(|node| unsafe { &(*node.as_ptr()).data })
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let prev = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.back,
+        };
+        prev.map(|node| unsafe { &(*node.as_ptr()).data })
+    }
+}
+
+// Same movement rules as Cursor, plus editing. Inserting or splicing "after" the
+// ghost means at the front of the list, and "before" the ghost means at the back.
+pub struct CursorMut<'a, T> {
+    current: Link<T>,
+    index: usize,
+    list: &'a mut DList<T>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => unsafe {
+                self.current = (*node.as_ptr()).next;
+                self.index += 1;
+            },
+            None => {
+                self.current = self.list.front;
+                self.index = 0;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(node) => unsafe {
+                self.current = (*node.as_ptr()).prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            },
+            None => {
+                self.current = self.list.back;
+                self.index = self.list.len.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.front,
+        };
+        next.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.back,
+        };
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    // Read-only view of the cursor, borrowing from this one
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            current: self.current,
+            index: self.index,
+            list: self.list,
+        }
+    }
+
+    pub fn insert_after(&mut self, data: T) {
+        let mut single = DList::new();
+        single.push_back(data);
+        self.splice_after(single);
+    }
+
+    pub fn insert_before(&mut self, data: T) {
+        let mut single = DList::new();
+        single.push_back(data);
+        self.splice_before(single);
+    }
+
+    // Removes the current element and moves the cursor onto the next one.
+    // Does nothing and returns None on the ghost.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        // SAFETY: node belongs to the list; its neighbours are relinked around it
+        // before the Box is rebuilt, so no dangling pointers to it remain
+        unsafe {
+            let boxed = Box::from_raw(node.as_ptr());
+            match boxed.prev {
+                Some(prev) => (*prev.as_ptr()).next = boxed.next,
+                None => self.list.front = boxed.next,
+            }
+            match boxed.next {
+                Some(next) => (*next.as_ptr()).prev = boxed.prev,
+                None => self.list.back = boxed.prev,
+            }
+            self.list.len -= 1;
+            self.current = boxed.next;
+            Some(boxed.data)
+        }
+    }
+
+    // Splits off everything after the current element into a new list.
+    // On the ghost the whole list is moved out.
+    pub fn split_after(&mut self) -> DList<T> {
+        match self.current {
+            None => {
+                self.index = 0;
+                std::mem::take(self.list)
+            }
+            Some(node) => unsafe {
+                let kept = self.index + 1;
+                let mut tail = DList::new();
+                if let Some(next) = (*node.as_ptr()).next.take() {
+                    (*next.as_ptr()).prev = None;
+                    tail.front = Some(next);
+                    tail.back = self.list.back;
+                    tail.len = self.list.len - kept;
+                }
+                self.list.back = Some(node);
+                self.list.len = kept;
+                tail
+            },
+        }
+    }
+
+    // Splits off everything before the current element into a new list.
+    // On the ghost the whole list is moved out.
+    pub fn split_before(&mut self) -> DList<T> {
+        match self.current {
+            None => {
+                self.index = 0;
+                std::mem::take(self.list)
+            }
+            Some(node) => unsafe {
+                let moved = self.index;
+                let mut head = DList::new();
+                if let Some(prev) = (*node.as_ptr()).prev.take() {
+                    (*prev.as_ptr()).next = None;
+                    head.front = self.list.front;
+                    head.back = Some(prev);
+                    head.len = moved;
+                }
+                self.list.front = Some(node);
+                self.list.len -= moved;
+                self.index = 0;
+                head
+            },
+        }
+    }
+
+    // Moves every element of other in right after the current element
+    pub fn splice_after(&mut self, mut other: DList<T>) {
+        let (other_front, other_back) = match (other.front.take(), other.back.take()) {
+            (Some(front), Some(back)) => (front, back),
+            _ => return,
+        };
+        let added = std::mem::replace(&mut other.len, 0);
+
+        // SAFETY: other has been emptied above, so its nodes now belong to self.list only
+        unsafe {
+            let (prev, next) = match self.current {
+                Some(node) => (Some(node), (*node.as_ptr()).next),
+                None => (None, self.list.front),
+            };
+            (*other_front.as_ptr()).prev = prev;
+            (*other_back.as_ptr()).next = next;
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = Some(other_front),
+                None => self.list.front = Some(other_front),
+            }
+            match next {
+                Some(next) => (*next.as_ptr()).prev = Some(other_back),
+                None => self.list.back = Some(other_back),
+            }
+        }
+
+        self.list.len += added;
+        // the ghost's index tracks the length
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    // Moves every element of other in right before the current element
+    pub fn splice_before(&mut self, mut other: DList<T>) {
+        let (other_front, other_back) = match (other.front.take(), other.back.take()) {
+            (Some(front), Some(back)) => (front, back),
+            _ => return,
+        };
+        let added = std::mem::replace(&mut other.len, 0);
+
+        unsafe {
+            let (prev, next) = match self.current {
+                Some(node) => ((*node.as_ptr()).prev, Some(node)),
+                None => (self.list.back, None),
+            };
+            (*other_front.as_ptr()).prev = prev;
+            (*other_back.as_ptr()).next = next;
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = Some(other_front),
+                None => self.list.front = Some(other_front),
+            }
+            match next {
+                Some(next) => (*next.as_ptr()).prev = Some(other_back),
+                None => self.list.back = Some(other_back),
+            }
+        }
+
+        self.list.len += added;
+        self.index += added;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_of(items: &[i32]) -> DList<i32> {
+        items.iter().copied().collect()
+    }
+
+    fn to_vec(list: &DList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_ll_push() {
+        let mut list = DList::new();
+        list.push_front(1);
+        list.push_front(2);
+        list.push_front(3);
+
+        assert_eq!(list.pop_front(), Some(3));
+        assert_eq!(list.pop_front(), Some(2));
+
+        list.push_front(5);
+        list.push_front(6);
+
+        assert_eq!(list.pop_front(), Some(6));
+        assert_eq!(list.pop_front(), Some(5));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), None);
+    }
+
+    #[test]
+    fn test_both_ends() {
+        let mut list = DList::new();
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.front(), None);
+
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list.back(), Some(&3));
+
+        if let Some(val) = list.back_mut() {
+            *val = 30;
+        }
+        assert_eq!(list.pop_back(), Some(30));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), Some(1));
+        assert_eq!(list.pop_back(), None);
+        assert!(list.is_empty());
+        assert_eq!(list.back(), None);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut list = DList::new();
+        list.push_front(2);
+        list.push_front(3);
+        list.push_front(1);
+
+        let mut iter = list.into_iter();
+
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(2));
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut list = DList::new();
+        list.push_front(2);
+        list.push_front(3);
+        list.push_front(1);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(list.iter().rev().collect::<Vec<_>>(), vec![&2, &3, &1]);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next_back(), Some(&2));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut list = DList::new();
+        list.push_front(2);
+        list.push_front(3);
+        list.push_front(1);
+
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.next(), Some(&mut 1));
+        assert_eq!(iter.next(), Some(&mut 3));
+        assert_eq!(iter.next(), Some(&mut 2));
+        assert_eq!(iter.next(), None);
+
+        for val in list.iter_mut().rev() {
+            *val *= 2;
+        }
+        assert_eq!(to_vec(&list), vec![2, 6, 4]);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut a = list_of(&[1, 2]);
+        let mut b = list_of(&[3, 4]);
+        a.append(&mut b);
+        assert_eq!(to_vec(&a), vec![1, 2, 3, 4]);
+        assert!(b.is_empty());
+
+        let mut empty = DList::new();
+        empty.append(&mut a);
+        assert_eq!(to_vec(&empty), vec![1, 2, 3, 4]);
+        assert_eq!(empty.back(), Some(&4));
+    }
+
+    #[test]
+    fn test_node_handles() {
+        let mut list = list_of(&[10, 20]);
+        let one = list.push_front_handle(1);
+        let two = list.push_front_handle(2);
+        let three = list.push_front_handle(3);
+        assert_eq!(to_vec(&list), vec![3, 2, 1, 10, 20]);
+
+        // every handle below is still in the list when it's used
+        unsafe {
+            assert_eq!(*list.get_by_handle(one), 1);
+            *list.get_mut_by_handle(two) = 22;
+
+            list.move_to_front_by_handle(one);
+            assert_eq!(to_vec(&list), vec![1, 3, 22, 10, 20]);
+            // already at the front
+            list.move_to_front_by_handle(one);
+            assert_eq!(to_vec(&list), vec![1, 3, 22, 10, 20]);
+
+            assert_eq!(list.remove_by_handle(three), 3);
+            assert_eq!(list.remove_by_handle(one), 1);
+            assert_eq!(to_vec(&list), vec![22, 10, 20]);
+            assert_eq!(list.front(), Some(&22));
+
+            assert_eq!(list.remove_by_handle(two), 22);
+        }
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![20, 10]);
+
+        let mut single = DList::new();
+        let only = single.push_front_handle(5);
+        assert_eq!(unsafe { single.remove_by_handle(only) }, 5);
+        assert!(single.is_empty());
+        assert_eq!(single.front(), None);
+        assert_eq!(single.back(), None);
+    }
+
+    #[test]
+    fn test_cursor_moves() {
+        let list = list_of(&[1, 2, 3]);
+        let mut cursor = list.cursor_front();
+
+        assert_eq!(cursor.current(), Some(&1));
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!(cursor.peek_next(), Some(&2));
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&3));
+
+        // off the back onto the ghost, then wrap to the front
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&1));
+        assert_eq!(cursor.peek_prev(), Some(&3));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&1));
+
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+
+        let back = list.cursor_back();
+        assert_eq!(back.current(), Some(&3));
+        assert_eq!(DList::<i32>::new().cursor_front().current(), None);
+    }
+
+    #[test]
+    fn test_cursor_insert_remove() {
+        let mut list = list_of(&[1, 3]);
+        {
+            let mut cursor = list.cursor_front_mut();
+            cursor.insert_after(2);
+            cursor.insert_before(0);
+            assert_eq!(cursor.index(), Some(1));
+            assert_eq!(cursor.current(), Some(&mut 1));
+
+            cursor.move_next();
+            assert_eq!(cursor.remove_current(), Some(2));
+            assert_eq!(cursor.current(), Some(&mut 3));
+            assert_eq!(cursor.remove_current(), Some(3));
+            assert_eq!(cursor.remove_current(), None);
+
+            // on the ghost, after means front and before means back
+            cursor.insert_after(-1);
+            cursor.insert_before(9);
+            assert_eq!(cursor.index(), None);
+            assert_eq!(cursor.as_cursor().peek_prev(), Some(&9));
+        }
+        assert_eq!(to_vec(&list), vec![-1, 0, 1, 9]);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![9, 1, 0, -1]);
+    }
+
+    #[test]
+    fn test_cursor_split() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+
+        let tail = cursor.split_after();
+        assert_eq!(to_vec(&tail), vec![3, 4, 5]);
+        assert_eq!(tail.back(), Some(&5));
+
+        let head = cursor.split_before();
+        assert_eq!(to_vec(&head), vec![1]);
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(to_vec(&list), vec![2]);
+        assert_eq!(list.len(), 1);
+        assert_eq!(tail.len() + head.len(), 4);
+
+        let mut whole = list_of(&[7, 8]);
+        let mut ghost = whole.cursor_back_mut();
+        ghost.move_next();
+        assert_eq!(to_vec(&ghost.split_after()), vec![7, 8]);
+        assert!(whole.is_empty());
+    }
+
+    #[test]
+    fn test_cursor_splice() {
+        let mut list = list_of(&[1, 5]);
+        {
+            let mut cursor = list.cursor_front_mut();
+            cursor.splice_after(list_of(&[2, 3, 4]));
+            assert_eq!(cursor.index(), Some(0));
+            cursor.splice_before(list_of(&[-1, 0]));
+            assert_eq!(cursor.index(), Some(2));
+            cursor.splice_after(DList::new());
+
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.splice_before(list_of(&[6]));
+            cursor.splice_after(list_of(&[-2]));
+        }
+        assert_eq!(to_vec(&list), vec![-2, -1, 0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(list.len(), 9);
+        assert_eq!(list.iter().rev().count(), 9);
+        assert_eq!(list.back(), Some(&6));
+        assert_eq!(list.front(), Some(&-2));
+    }
+
+    #[test]
+    fn test_dlist_traits() {
+        let list = list_of(&[1, 2, 3]);
+        let cloned = list.clone();
+        assert_eq!(list, cloned);
+        assert!(cloned.contains(&2));
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(DList::<i32>::default(), DList::new());
+
+        let long: DList<u32> = (0..200_000).collect();
+        assert_eq!(long.len(), 200_000);
+    }
+}
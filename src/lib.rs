@@ -3,4 +3,10 @@ pub mod strings;
 pub mod search;
 
 pub mod linked_list;
+pub mod doubly_linked_list;
+pub mod persistent_list;
+pub mod arena_list;
+pub mod concurrent_stack;
+pub mod lru_cache;
+pub mod skip_list;
 pub mod math;
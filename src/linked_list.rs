@@ -1,72 +1,93 @@
-use std::cmp::PartialEq;
-use std::fmt::Debug;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ptr;
 
 // Need a Node type to hold data and reference to next Node in LL
-struct Node<T: PartialEq + Debug> {
+struct Node<T> {
     data: T,
     next: Link<T>,
 }
 
 // Need a Link type to hold a reference to a boxed Node or None
-type Link<T: PartialEq + Debug> = Option<Box<Node<T>>>;
+type Link<T> = Option<Box<Node<T>>>;
 
 // Need a LL type to join everything together by maintaining a pointer to a specific Link
-struct LinkedList<T: PartialEq + Debug> {
+// Keeps a running count of the nodes so len() doesn't have to walk the list
+pub struct LinkedList<T> {
     head: Link<T>,
+    len: usize,
 }
 
-impl<T: PartialEq + Debug> LinkedList<T> {
-    fn new() -> Self {
-        LinkedList { head: None }
+impl<T> LinkedList<T> {
+    pub fn new() -> Self {
+        LinkedList { head: None, len: 0 }
     }
+
+    // Number of nodes currently in the Linked List
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    // Drops every node in the list. Replacing self runs the iterative Drop below
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
     // Method to push Nodes onto the Linked List
-    fn push(&mut self, data: T) {
+    pub fn push(&mut self, data: T) {
         let new_node = Box::new(Node {
             data,
             next: self.head.take(),
         });
 
-        self.head = Some(new_node)
+        self.head = Some(new_node);
+        self.len += 1;
     }
     // Method to pop Nodes from the Linked List
-    fn pop(&mut self) -> Option<T> {
+    pub fn pop(&mut self) -> Option<T> {
         // Take the box stored at the head pointer and map over the node in a closure
         self.head.take().map(|node| {
             // link to the next node in the series
             self.head = node.next;
+            self.len -= 1;
             // return the data stored in the popped noded
             node.data
         })
     }
     // Method to peek at the Node within the head pointer's Box
-    fn peek(&self) -> Option<&T> {
+    pub fn peek(&self) -> Option<&T> {
         self.head.as_ref().map(|node| &node.data)
     }
 
     // Method to obtain a mutable reference to box innards
-    fn peek_mut(&mut self) -> Option<&mut T> {
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
         self.head.as_mut().map(|node| &mut node.data)
     }
+}
 
+impl<T: PartialEq> LinkedList<T> {
     // Method to find an element in the LinkedList and return its index
-    fn find(&mut self, target: T) -> Option<usize> {
-        let mut target_idx = Some(0);
-        // borrow a reference to the head node 
+    pub fn find(&self, target: &T) -> Option<usize> {
+        let mut target_idx = 0;
+        // borrow a reference to the head node
         let mut curr_node = self.head.as_ref();
 
         // traverse all nodes in the linked list:
         // while there is Some data inside the current node
         while let Some(boxed_data) = curr_node {
-            // check to see if the target value is contained in the node
-            let node_data = curr_node.map(|node| &node.data);
-
-            // if it does, return the index of said node
-            if *node_data.unwrap() == target {
-                return target_idx
-            } else {
-                // otherwise, increment the target_idx counter
-                target_idx.as_mut().map(|idx| *idx+=1);
+            // if the node contains the target value, return the index of said node
+            if boxed_data.data == *target {
+                return Some(target_idx);
             }
+            // otherwise, increment the target_idx counter
+            target_idx += 1;
 
             // Move to next node in linked list
             curr_node = boxed_data.next.as_ref()
@@ -75,9 +96,404 @@ impl<T: PartialEq + Debug> LinkedList<T> {
         // if the target is not found, will evaluate to None
         None
     }
+
+    // Returns true if any node in the list holds a value equal to target
+    pub fn contains(&self, target: &T) -> bool {
+        self.iter().any(|data| data == target)
+    }
+
+    // Removes the first node holding a value equal to target
+    pub fn remove_first(&mut self, target: &T) -> Option<T> {
+        let idx = self.find(target)?;
+        self.remove(idx)
+    }
+}
+
+impl<T> LinkedList<T> {
+    // Index of the first element that satisfies the predicate
+    pub fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().position(predicate)
+    }
+
+    // Returns the first Some produced by f while walking from the head
+    pub fn find_map<B, F>(&self, f: F) -> Option<B>
+    where
+        F: FnMut(&T) -> Option<B>,
+    {
+        self.iter().find_map(f)
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        self.iter().nth(idx)
+    }
+
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.iter_mut().nth(idx)
+    }
+
+    // Inserts data so that it ends up at position idx, shifting later nodes back.
+    // Panics if idx is greater than the length of the list, like Vec::insert
+    pub fn insert(&mut self, idx: usize, data: T) {
+        assert!(idx <= self.len, "insert index {} out of bounds (len {})", idx, self.len);
+
+        let link = self.link_at(idx).unwrap();
+        let next = link.take();
+        *link = Some(Box::new(Node { data, next }));
+        self.len += 1;
+    }
+
+    // Unlinks the node at idx and returns its data, or None if idx is past the end
+    pub fn remove(&mut self, idx: usize) -> Option<T> {
+        let link = self.link_at(idx)?;
+        let mut removed = link.take()?;
+        *link = removed.next.take();
+        self.len -= 1;
+
+        Some(removed.data)
+    }
+
+    // Keeps only the elements the predicate returns true for
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.extract_if(|data| !keep(data)).for_each(drop);
+    }
+
+    // Lazily removes and yields the elements matching the predicate.
+    // Nodes are only visited as the iterator is advanced, so dropping it early
+    // leaves the rest of the list untouched
+    pub fn extract_if<F>(&mut self, predicate: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let LinkedList { head, len } = self;
+        ExtractIf {
+            link: Some(head),
+            len,
+            predicate,
+        }
+    }
+
+    // Borrows the Link that holds the node at idx (the empty Link at the end when idx == len)
+    fn link_at(&mut self, idx: usize) -> Option<&mut Link<T>> {
+        let mut link = &mut self.head;
+        for _ in 0..idx {
+            link = &mut link.as_mut()?.next;
+        }
+
+        Some(link)
+    }
+}
+
+pub struct ExtractIf<'a, T, F> {
+    link: Option<&'a mut Link<T>>,
+    len: &'a mut usize,
+    predicate: F,
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(link) = self.link.take() {
+            let boxed_data = link.as_mut()?;
+
+            if (self.predicate)(&mut boxed_data.data) {
+                // splice the matching node out and stay on the same Link,
+                // which now holds the node that followed it
+                let mut removed = link.take().unwrap();
+                *link = removed.next.take();
+                *self.len -= 1;
+                self.link = Some(link);
+                return Some(removed.data);
+            }
+
+            // otherwise step into the next Link
+            self.link = link.as_mut().map(|node| &mut node.next);
+        }
+
+        None
+    }
+}
+
+impl<T> LinkedList<T> {
+    // Reverses the list in place by flipping each node's next pointer
+    pub fn reverse(&mut self) {
+        let mut prev: Link<T> = None;
+        let mut curr_node = self.head.take();
+
+        while let Some(mut boxed_data) = curr_node {
+            // detach the rest of the list, then point this node back at prev
+            curr_node = boxed_data.next.take();
+            boxed_data.next = prev;
+            prev = Some(boxed_data);
+        }
+
+        self.head = prev;
+    }
+
+    // Splits the list in two at the given index. Self keeps the nodes
+    // before `at` and the returned list gets `at` and everything after it.
+    // Panics if `at` is greater than the length of the list, like Vec::split_off
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "split index {} out of bounds (len {})", at, self.len);
+
+        let tail = Self::detach_after(&mut self.head, at);
+        let tail_len = self.len - at;
+        self.len = at;
+
+        LinkedList {
+            head: tail,
+            len: tail_len,
+        }
+    }
+
+    // Consuming version of split_off that hands back both halves
+    pub fn split_at(mut self, at: usize) -> (Self, Self) {
+        let tail = self.split_off(at);
+        (self, tail)
+    }
+
+    // Finds the middle node with a slow and a fast pointer.
+    // For an even number of nodes this returns the second of the two middles
+    pub fn middle(&self) -> Option<&T> {
+        let mut slow = self.head.as_deref();
+        let mut fast = self.head.as_deref();
+
+        // the fast pointer moves two nodes for every one the slow pointer moves,
+        // so slow is halfway along when fast runs off the end
+        while let Some(node) = fast.and_then(|node| node.next.as_deref()) {
+            slow = slow.and_then(|node| node.next.as_deref());
+            fast = node.next.as_deref();
+        }
+
+        slow.map(|node| &node.data)
+    }
+
+    // Removes the nth node counting back from the tail, where n = 1 is the last node
+    pub fn remove_nth_from_end(&mut self, n: usize) -> Option<T> {
+        if n == 0 || n > self.len {
+            return None;
+        }
+
+        // the list knows its own length, so the node is (len - n) steps from the head
+        self.remove(self.len - n)
+    }
+
+    // Stable merge sort that relinks the existing nodes instead of reallocating
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let head = self.head.take();
+        self.head = Self::merge_sort(head, self.len, &mut compare);
+    }
+
+    // Top-down merge sort over the Box chain. Splitting by count keeps the
+    // recursion depth at log(n), so long lists are fine here
+    fn merge_sort<F>(mut head: Link<T>, len: usize, compare: &mut F) -> Link<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if len < 2 {
+            return head;
+        }
+
+        let mid = len / 2;
+        let right = Self::detach_after(&mut head, mid);
+
+        let left = Self::merge_sort(head, mid, compare);
+        let right = Self::merge_sort(right, len - mid, compare);
+
+        Self::merge_links(left, right, compare)
+    }
+
+    // Merges two sorted chains into one. On ties the node from `left` goes
+    // first, which is what keeps the sort stable
+    fn merge_links<F>(mut left: Link<T>, mut right: Link<T>, compare: &mut F) -> Link<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut head: Link<T> = None;
+        let mut tail = &mut head;
+
+        while let (Some(l_node), Some(r_node)) = (left.as_ref(), right.as_ref()) {
+            let source = if compare(&r_node.data, &l_node.data) == Ordering::Less {
+                &mut right
+            } else {
+                &mut left
+            };
+
+            // unhook the front node of whichever chain won and hang it off the tail
+            let mut node = source.take().unwrap();
+            *source = node.next.take();
+            tail = &mut tail.insert(node).next;
+        }
+
+        // one side is exhausted, so the rest of the other can be linked in whole
+        *tail = if left.is_some() { left } else { right };
+
+        head
+    }
+
+    // Takes everything after the first `at` nodes off the end of the chain
+    fn detach_after(link: &mut Link<T>, at: usize) -> Link<T> {
+        let mut link = link;
+        for _ in 0..at {
+            link = &mut link.as_mut()?.next;
+        }
+
+        link.take()
+    }
+}
+
+impl<T: Ord> LinkedList<T> {
+    pub fn sort(&mut self) {
+        self.sort_by(|a, b| a.cmp(b));
+    }
+
+    // Merges another sorted list into this sorted list, leaving self sorted.
+    // Both lists are expected to already be in ascending order
+    pub fn merge_sorted(&mut self, mut other: Self) {
+        let left = self.head.take();
+        let right = other.head.take();
+
+        self.head = Self::merge_links(left, right, &mut |a: &T, b: &T| a.cmp(b));
+        self.len += other.len;
+        other.len = 0;
+    }
+}
+
+impl<T: PartialEq> LinkedList<T> {
+    // Removes consecutive repeated elements, so a sorted list ends up with no duplicates
+    pub fn dedup(&mut self) {
+        let mut curr_node = self.head.as_mut();
+
+        while let Some(boxed_data) = curr_node {
+            // drop every following node that repeats this one's data
+            while boxed_data
+                .next
+                .as_ref()
+                .is_some_and(|next| next.data == boxed_data.data)
+            {
+                let mut removed = boxed_data.next.take().unwrap();
+                boxed_data.next = removed.next.take();
+                self.len -= 1;
+            }
+
+            curr_node = boxed_data.next.as_mut();
+        }
+    }
+}
+
+// The Box based list can never form a cycle, so cycle detection works on a raw
+// node form instead: nodes sit in a slice and point at each other by index,
+// which lets a node's next point back at any earlier node.
+pub struct RawNode<T> {
+    pub data: T,
+    pub next: Option<usize>,
 }
+
+// Describes a cycle found by walking next pointers from the head.
+// `start` is the index of the first node on the cycle, `offset` is the number of
+// steps from the head to reach it and `len` is the number of nodes in the loop
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cycle {
+    pub start: usize,
+    pub offset: usize,
+    pub len: usize,
+}
+
+// Floyd's tortoise and hare: the hare moves two steps for every step the tortoise
+// takes, so they can only meet if the walk loops back on itself
+pub fn find_cycle_floyd<T>(nodes: &[RawNode<T>], head: Option<usize>) -> Option<Cycle> {
+    let next = |idx: usize| nodes.get(idx).and_then(|node| node.next);
+    let head = head?;
+
+    let mut tortoise = head;
+    let mut hare = head;
+    loop {
+        tortoise = next(tortoise)?;
+        hare = next(next(hare)?)?;
+        if tortoise == hare {
+            break;
+        }
+    }
+
+    // restarting the tortoise from the head, both pointers meet at the cycle start
+    let mut offset = 0;
+    tortoise = head;
+    while tortoise != hare {
+        tortoise = next(tortoise)?;
+        hare = next(hare)?;
+        offset += 1;
+    }
+
+    // walk once around the loop to measure it
+    let mut len = 1;
+    hare = next(tortoise)?;
+    while hare != tortoise {
+        hare = next(hare)?;
+        len += 1;
+    }
+
+    Some(Cycle {
+        start: tortoise,
+        offset,
+        len,
+    })
+}
+
+// Brent's algorithm: the tortoise teleports to the hare every power of two steps,
+// which finds the loop length directly and calls next fewer times than Floyd
+pub fn find_cycle_brent<T>(nodes: &[RawNode<T>], head: Option<usize>) -> Option<Cycle> {
+    let next = |idx: usize| nodes.get(idx).and_then(|node| node.next);
+    let head = head?;
+
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = head;
+    let mut hare = next(head)?;
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare;
+            power *= 2;
+            len = 0;
+        }
+        hare = next(hare)?;
+        len += 1;
+    }
+
+    // put the hare `len` steps ahead of the tortoise, then advance both
+    // together until they meet at the start of the cycle
+    tortoise = head;
+    hare = head;
+    for _ in 0..len {
+        hare = next(hare)?;
+    }
+
+    let mut offset = 0;
+    while tortoise != hare {
+        tortoise = next(tortoise)?;
+        hare = next(hare)?;
+        offset += 1;
+    }
+
+    Some(Cycle {
+        start: tortoise,
+        offset,
+        len,
+    })
+}
+
 // Need to implement the Drop trait because dropping boxes is not tail recursive
-impl<T: PartialEq + Debug> Drop for LinkedList<T> {
+impl<T> Drop for LinkedList<T> {
     fn drop(&mut self) {
         let mut curr_node = self.head.take();
         while let Some(mut boxed_data) = curr_node {
@@ -86,71 +502,493 @@ impl<T: PartialEq + Debug> Drop for LinkedList<T> {
     }
 }
 
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Collecting keeps the order of the source iterator, so the first item
+// yielded ends up at the head of the list
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+// Extending appends to the tail of the list rather than pushing onto the head
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut added = 0;
+        // walk down to the empty Link at the end of the list
+        let mut tail = &mut self.head;
+        while let Some(node) = tail {
+            tail = &mut node.next;
+        }
+
+        // hang each new node off the current tail, then step into its next Link
+        for data in iter {
+            let node = tail.insert(Box::new(Node { data, next: None }));
+            tail = &mut node.next;
+            added += 1;
+        }
+
+        self.len += added;
+    }
+}
+
+impl<'a, T: 'a + Copy> Extend<&'a T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+// Clone rebuilds the chain node by node in the same order
+impl<T: Clone> Clone for LinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug> Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// Two lists are equal when they hold equal elements in the same order
+impl<T: PartialEq> PartialEq for LinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for LinkedList<T> {}
+
+// Lists are compared lexicographically, starting at the head
+impl<T: PartialOrd> PartialOrd for LinkedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for LinkedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+// Hash the length first so lists of lists don't collide on concatenation
+impl<T: Hash> Hash for LinkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for data in self.iter() {
+            data.hash(state);
+        }
+    }
+}
+
 // Need to implement a way to iterate over the LinkedList
 // Uses a Tuple Struct to wrap the LinkedList Type
-pub struct IntoIter<T: PartialEq + Debug>(LinkedList<T>);
+pub struct IntoIter<T>(LinkedList<T>);
 
-impl<T: PartialEq + Debug> Iterator for IntoIter<T> {
+impl<T> Iterator for IntoIter<T> {
     type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
         self.0.pop()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
 }
 
-impl<T: PartialEq + Debug> LinkedList<T> {
-    // Method to move self into an iterator
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+// Moves the list into an iterator that pops from the head
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
     fn into_iter(self) -> IntoIter<T> {
         IntoIter(self)
     }
 }
 
-pub struct Iter<'a, T: PartialEq + Debug> {
+pub struct Iter<'a, T> {
     next: Option<&'a Node<T>>,
+    len: usize,
 }
 
-impl<'a, T: PartialEq + Debug> Iterator for Iter<'a, T> {
+impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
     fn next(&mut self) -> Option<Self::Item> {
         self.next.map(|node| {
-            self.next = node.next.as_ref().map(|node| &**node);
+            self.next = node.next.as_deref();
+            self.len -= 1;
             &node.data
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
-impl<T: PartialEq + Debug> LinkedList<T> {
-    fn iter(&mut self) -> Iter<T> {
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+// Iter only holds a shared reference, so copying the cursor is cheap
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            next: self.next,
+            len: self.len,
+        }
+    }
+}
+
+impl<T> LinkedList<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             // Deref the Box before taking the reference to underlying node
-            next: self.head.as_ref().map(|node| &**node),
+            next: self.head.as_deref(),
             // can also be written like so:
             // next: self.head.as_ref().map::<&Node<T>, _>(|node| &node)
+            len: self.len,
         }
     }
 }
 
-pub struct IterMut<'a, T: PartialEq + Debug> {
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+pub struct IterMut<'a, T> {
     next: Option<&'a mut Node<T>>,
+    len: usize,
 }
 
-impl<'a, T: PartialEq + Debug> Iterator for IterMut<'a, T> {
+impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
     fn next(&mut self) -> Option<Self::Item> {
         self.next.take().map(|node| {
-            self.next = node.next.as_mut().map(|node| &mut **node);
+            self.next = node.next.as_deref_mut();
+            self.len -= 1;
             &mut node.data
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
-impl<T: PartialEq + Debug> LinkedList<T> {
-    fn iter_mut(&mut self) -> IterMut<T> {
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> LinkedList<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
-            next: self.head.as_mut().map(|node| &mut **node),
+            next: self.head.as_deref_mut(),
+            len: self.len,
         }
     }
 }
 
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+// Singly linked FIFO queue. Nodes are pushed at the tail and popped from the head,
+// so on top of the head it keeps a raw pointer to the last node for O(1) push_back.
+//
+// A Box can't be aliased by the tail pointer, so every link in the queue is a raw
+// pointer made with Box::into_raw and only turned back into a Box when the node is
+// popped. Mixing Box owned links with a raw tail would break the aliasing rules
+// Miri checks, because moving or touching the Box invalidates the raw pointer.
+type QueueLink<T> = *mut QueueNode<T>;
+
+struct QueueNode<T> {
+    data: T,
+    next: QueueLink<T>,
+}
+
+pub struct Queue<T> {
+    head: QueueLink<T>,
+    tail: QueueLink<T>,
+    len: usize,
+    // tells the compiler we own values of type T
+    _owns: PhantomData<T>,
+}
+
+// The queue owns its nodes outright, so it can move between threads like a Vec<T>
+unsafe impl<T: Send> Send for Queue<T> {}
+unsafe impl<T: Sync> Sync for Queue<T> {}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Queue {
+            head: ptr::null_mut(),
+            tail: ptr::null_mut(),
+            len: 0,
+            _owns: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_null()
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    // Adds a node behind the current tail
+    pub fn push_back(&mut self, data: T) {
+        let new_tail = Box::into_raw(Box::new(QueueNode {
+            data,
+            next: ptr::null_mut(),
+        }));
+
+        // SAFETY: tail is either null or points at the last node, which we own
+        unsafe {
+            if self.tail.is_null() {
+                self.head = new_tail;
+            } else {
+                (*self.tail).next = new_tail;
+            }
+        }
+
+        self.tail = new_tail;
+        self.len += 1;
+    }
+
+    // Takes the node at the head of the queue
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.head.is_null() {
+            return None;
+        }
+
+        // SAFETY: head is non-null and was made by Box::into_raw in push_back,
+        // and it is unlinked here so nothing else will free it
+        unsafe {
+            let boxed = Box::from_raw(self.head);
+            self.head = boxed.next;
+            if self.head.is_null() {
+                self.tail = ptr::null_mut();
+            }
+            self.len -= 1;
+
+            Some(boxed.data)
+        }
+    }
+
+    pub fn peek_front(&self) -> Option<&T> {
+        unsafe { self.head.as_ref().map(|node| &node.data) }
+    }
+
+    pub fn peek_front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.head.as_mut().map(|node| &mut node.data) }
+    }
+
+    pub fn peek_back(&self) -> Option<&T> {
+        unsafe { self.tail.as_ref().map(|node| &node.data) }
+    }
+
+    pub fn peek_back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.tail.as_mut().map(|node| &mut node.data) }
+    }
+
+    // Moves every node of other onto the back of this queue in O(1), leaving other empty
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+
+        // SAFETY: both tails point at nodes owned by their queues, and other gives
+        // up its pointers below so the nodes end up owned by self only
+        unsafe {
+            if self.tail.is_null() {
+                self.head = other.head;
+            } else {
+                (*self.tail).next = other.head;
+            }
+        }
+
+        self.tail = other.tail;
+        self.len += other.len;
+
+        other.head = ptr::null_mut();
+        other.tail = ptr::null_mut();
+        other.len = 0;
+    }
+
+    pub fn iter(&self) -> QueueIter<'_, T> {
+        QueueIter {
+            next: unsafe { self.head.as_ref() },
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> QueueIterMut<'_, T> {
+        QueueIterMut {
+            next: unsafe { self.head.as_mut() },
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for Queue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = Queue::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T> Extend<T> for Queue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for data in iter {
+            self.push_back(data);
+        }
+    }
+}
+
+impl<T: Clone> Clone for Queue<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug> Debug for Queue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Queue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for Queue<T> {}
+
+pub struct QueueIntoIter<T>(Queue<T>);
+
+impl<T> Iterator for QueueIntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> ExactSizeIterator for QueueIntoIter<T> {}
+
+impl<T> IntoIterator for Queue<T> {
+    type Item = T;
+    type IntoIter = QueueIntoIter<T>;
+
+    fn into_iter(self) -> QueueIntoIter<T> {
+        QueueIntoIter(self)
+    }
+}
+
+pub struct QueueIter<'a, T> {
+    next: Option<&'a QueueNode<T>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for QueueIter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            // SAFETY: the shared borrow of the queue keeps every node alive
+            self.next = unsafe { node.next.as_ref() };
+            self.len -= 1;
+            &node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for QueueIter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a Queue<T> {
+    type Item = &'a T;
+    type IntoIter = QueueIter<'a, T>;
+
+    fn into_iter(self) -> QueueIter<'a, T> {
+        self.iter()
+    }
+}
+
+pub struct QueueIterMut<'a, T> {
+    next: Option<&'a mut QueueNode<T>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for QueueIterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.take().map(|node| {
+            // SAFETY: each node is visited once, so the &mut never alias
+            self.next = unsafe { node.next.as_mut() };
+            self.len -= 1;
+            &mut node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for QueueIterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a mut Queue<T> {
+    type Item = &'a mut T;
+    type IntoIter = QueueIterMut<'a, T>;
+
+    fn into_iter(self) -> QueueIterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,7 +1025,9 @@ mod tests {
 
         // Check to make sure you can mutate the mut ref
         // Correct way:
-        list.peek_mut().map(|val| *val = 42);
+        if let Some(val) = list.peek_mut() {
+            *val = 42;
+        }
 
         // Incorrect way:
         // list.peek_mut().map(|&mut val| val = 42);
@@ -246,10 +1086,407 @@ mod tests {
         list.push(3);
         list.push(1);
 
-        assert_eq!(list.find(1), Some(0));
-        assert_eq!(list.find(3), Some(1));
-        assert_eq!(list.find(2), Some(2));
-        assert_eq!(list.find(5), None);
+        assert_eq!(list.find(&1), Some(0));
+        assert_eq!(list.find(&3), Some(1));
+        assert_eq!(list.find(&2), Some(2));
+        assert_eq!(list.find(&5), None);
+    }
+
+    #[test]
+    fn test_len_and_clear() {
+        let mut list = LinkedList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+
+        list.push(1);
+        list.push(2);
+        assert_eq!(list.len(), 2);
+        assert!(!list.is_empty());
+
+        list.pop();
+        assert_eq!(list.len(), 1);
+
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop(), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        let list: LinkedList<i32> = vec![4, 8, 15].into_iter().collect();
+
+        assert!(list.contains(&8));
+        assert!(!list.contains(&16));
+        assert!(!LinkedList::<i32>::new().contains(&0));
+    }
+
+    #[test]
+    fn test_collect_and_extend() {
+        let mut list: LinkedList<i32> = (1..4).collect();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        list.extend(vec![4, 5]);
+        list.extend(&[6]);
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.peek(), Some(&1));
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_ref_into_iter() {
+        let mut list: LinkedList<i32> = (1..4).collect();
+
+        for val in &mut list {
+            *val *= 10;
+        }
+
+        let mut seen = Vec::new();
+        for val in &list {
+            seen.push(*val);
+        }
+        assert_eq!(seen, vec![10, 20, 30]);
+        assert_eq!(list.iter().len(), 3);
+    }
+
+    #[test]
+    fn test_list_traits() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of(list: &LinkedList<i32>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            list.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let list: LinkedList<i32> = vec![1, 2, 3].into_iter().collect();
+        let cloned = list.clone();
+
+        assert_eq!(list, cloned);
+        assert_eq!(hash_of(&list), hash_of(&cloned));
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(LinkedList::<i32>::default(), LinkedList::new());
+
+        let shorter: LinkedList<i32> = vec![1, 2].into_iter().collect();
+        let bigger: LinkedList<i32> = vec![1, 3].into_iter().collect();
+        assert_ne!(list, shorter);
+        assert!(shorter < list);
+        assert!(list < bigger);
+        assert_eq!(list.cmp(&cloned), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_long_list_drop() {
+        let list: LinkedList<u32> = (0..200_000).collect();
+        assert_eq!(list.len(), 200_000);
+        let cloned = list.clone();
+        drop(list);
+        assert_eq!(cloned.iter().last(), Some(&199_999));
+    }
+
+    fn list_of(items: &[i32]) -> LinkedList<i32> {
+        items.iter().copied().collect()
+    }
+
+    fn to_vec(list: &LinkedList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list = list_of(&[1, 2, 3, 4]);
+        list.reverse();
+        assert_eq!(to_vec(&list), vec![4, 3, 2, 1]);
+        assert_eq!(list.len(), 4);
+
+        let mut empty = LinkedList::<i32>::new();
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_split() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        let tail = list.split_off(2);
+        assert_eq!(to_vec(&list), vec![1, 2]);
+        assert_eq!(to_vec(&tail), vec![3, 4, 5]);
+        assert_eq!((list.len(), tail.len()), (2, 3));
+
+        let (left, right) = tail.split_at(3);
+        assert_eq!(to_vec(&left), vec![3, 4, 5]);
+        assert!(right.is_empty());
+
+        let (left, right) = list.split_at(0);
+        assert!(left.is_empty());
+        assert_eq!(to_vec(&right), vec![1, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_out_of_bounds() {
+        let mut list = list_of(&[1, 2]);
+        list.split_off(3);
+    }
+
+    #[test]
+    fn test_middle() {
+        assert_eq!(list_of(&[]).middle(), None);
+        assert_eq!(list_of(&[1]).middle(), Some(&1));
+        assert_eq!(list_of(&[1, 2, 3]).middle(), Some(&2));
+        assert_eq!(list_of(&[1, 2, 3, 4]).middle(), Some(&3));
+    }
+
+    #[test]
+    fn test_remove_nth_from_end() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        assert_eq!(list.remove_nth_from_end(2), Some(4));
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 5]);
+        assert_eq!(list.remove_nth_from_end(4), Some(1));
+        assert_eq!(list.remove_nth_from_end(1), Some(5));
+        assert_eq!(list.remove_nth_from_end(0), None);
+        assert_eq!(list.remove_nth_from_end(3), None);
+        assert_eq!(to_vec(&list), vec![2, 3]);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        let mut list = list_of(&[1, 4, 7]);
+        list.merge_sorted(list_of(&[2, 3, 8, 9]));
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 4, 7, 8, 9]);
+        assert_eq!(list.len(), 7);
+
+        let mut empty = LinkedList::new();
+        empty.merge_sorted(list_of(&[1, 2]));
+        assert_eq!(to_vec(&empty), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut list = list_of(&[5, 1, 4, 2, 3, 2]);
+        list.sort();
+        assert_eq!(to_vec(&list), vec![1, 2, 2, 3, 4, 5]);
+
+        // sort_by is stable: equal keys keep their original order
+        let mut pairs: LinkedList<(i32, char)> =
+            vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')].into_iter().collect();
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            pairs.into_iter().collect::<Vec<_>>(),
+            vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]
+        );
+
+        let mut big: LinkedList<i32> = (0..10_000).rev().collect();
+        big.sort();
+        assert!(big.iter().zip(0..).all(|(a, b)| *a == b));
+        assert_eq!(big.len(), 10_000);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut list = list_of(&[1, 1, 2, 3, 3, 3, 1]);
+        list.dedup();
+        assert_eq!(to_vec(&list), vec![1, 2, 3, 1]);
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 2
+        let looped: Vec<RawNode<i32>> = (0..5)
+            .map(|i| RawNode {
+                data: i as i32,
+                next: Some(if i == 4 { 2 } else { i + 1 }),
+            })
+            .collect();
+        let expected = Some(Cycle {
+            start: 2,
+            offset: 2,
+            len: 3,
+        });
+        assert_eq!(find_cycle_floyd(&looped, Some(0)), expected);
+        assert_eq!(find_cycle_brent(&looped, Some(0)), expected);
+
+        // a node pointing at itself
+        let single = vec![RawNode { data: 0, next: Some(0) }];
+        let expected = Some(Cycle {
+            start: 0,
+            offset: 0,
+            len: 1,
+        });
+        assert_eq!(find_cycle_floyd(&single, Some(0)), expected);
+        assert_eq!(find_cycle_brent(&single, Some(0)), expected);
+
+        // 0 -> 1 -> 2 with no loop
+        let straight: Vec<RawNode<i32>> = (0..3)
+            .map(|i| RawNode {
+                data: i as i32,
+                next: if i == 2 { None } else { Some(i + 1) },
+            })
+            .collect();
+        assert_eq!(find_cycle_floyd(&straight, Some(0)), None);
+        assert_eq!(find_cycle_brent(&straight, Some(0)), None);
+        assert_eq!(find_cycle_floyd(&straight, None), None);
+    }
+
+    #[test]
+    fn test_predicate_search() {
+        let list = list_of(&[3, 8, 5, 12]);
+
+        assert_eq!(list.position(|x| x % 2 == 0), Some(1));
+        assert_eq!(list.position(|x| *x > 100), None);
+        assert_eq!(list.find_map(|x| if *x > 4 { Some(x * 10) } else { None }), Some(80));
+        assert_eq!(list.find(&5), Some(2));
+        assert_eq!(list.get(3), Some(&12));
+        assert_eq!(list.get(4), None);
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut list = list_of(&[1, 3]);
+        list.insert(1, 2);
+        list.insert(0, 0);
+        list.insert(4, 4);
+        assert_eq!(to_vec(&list), vec![0, 1, 2, 3, 4]);
+        assert_eq!(list.len(), 5);
+
+        if let Some(val) = list.get_mut(2) {
+            *val = 20;
+        }
+        assert_eq!(list.remove(2), Some(20));
+        assert_eq!(list.remove(0), Some(0));
+        assert_eq!(list.remove(3), None);
+        assert_eq!(list.remove_first(&3), Some(3));
+        assert_eq!(list.remove_first(&3), None);
+        assert_eq!(to_vec(&list), vec![1, 4]);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_insert_out_of_bounds() {
+        let mut list = list_of(&[1]);
+        list.insert(2, 5);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut list = list_of(&[1, 2, 3, 4, 5, 6]);
+        list.retain(|x| x % 3 != 0);
+        assert_eq!(to_vec(&list), vec![1, 2, 4, 5]);
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut list = list_of(&[2, 1, 4, 6, 7, 8]);
+        let evens: Vec<i32> = list.extract_if(|x| *x % 2 == 0).collect();
+        assert_eq!(evens, vec![2, 4, 6, 8]);
+        assert_eq!(to_vec(&list), vec![1, 7]);
+        assert_eq!(list.len(), 2);
+
+        // only the nodes actually visited are removed
+        let mut list = list_of(&[1, 2, 3, 4]);
+        let first = list.extract_if(|x| *x > 1).next();
+        assert_eq!(first, Some(2));
+        assert_eq!(to_vec(&list), vec![1, 3, 4]);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_queue_push_pop() {
+        let mut queue = Queue::new();
+        assert_eq!(queue.pop_front(), None);
+
+        queue.push_back(1);
+        queue.push_back(2);
+        queue.push_back(3);
+        assert_eq!(queue.len(), 3);
+
+        assert_eq!(queue.pop_front(), Some(1));
+        assert_eq!(queue.pop_front(), Some(2));
+
+        queue.push_back(4);
+        queue.push_back(5);
+
+        assert_eq!(queue.pop_front(), Some(3));
+        assert_eq!(queue.pop_front(), Some(4));
+        assert_eq!(queue.pop_front(), Some(5));
+        assert_eq!(queue.pop_front(), None);
+
+        // the tail has to be reset when the queue empties out
+        queue.push_back(6);
+        queue.push_back(7);
+        assert_eq!(queue.pop_front(), Some(6));
+        assert_eq!(queue.pop_front(), Some(7));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_queue_peek() {
+        let mut queue = Queue::new();
+        assert_eq!(queue.peek_front(), None);
+        assert_eq!(queue.peek_back_mut(), None);
+
+        queue.push_back(1);
+        queue.push_back(2);
+        queue.push_back(3);
+        assert_eq!(queue.peek_front(), Some(&1));
+        assert_eq!(queue.peek_back(), Some(&3));
+
+        if let Some(val) = queue.peek_front_mut() {
+            *val = 10;
+        }
+        if let Some(val) = queue.peek_back_mut() {
+            *val = 30;
+        }
+        // mutating through a peek and then pushing must not disturb the tail
+        queue.push_back(4);
+        assert_eq!(queue.pop_front(), Some(10));
+        assert_eq!(queue.peek_back(), Some(&4));
+        assert_eq!(queue.into_iter().collect::<Vec<_>>(), vec![2, 30, 4]);
+    }
+
+    #[test]
+    fn test_queue_iters() {
+        let mut queue: Queue<i32> = (1..4).collect();
+
+        let mut iter = queue.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), None);
+
+        for val in &mut queue {
+            *val *= 10;
+        }
+        queue.push_back(40);
+        assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![10, 20, 30, 40]);
+        assert_eq!(format!("{:?}", queue), "[10, 20, 30, 40]");
+        assert_eq!(queue.clone(), queue);
+    }
+
+    #[test]
+    fn test_queue_append() {
+        let mut first: Queue<i32> = (1..3).collect();
+        let mut second: Queue<i32> = (3..5).collect();
+
+        first.append(&mut second);
+        assert!(second.is_empty());
+        assert_eq!(second.pop_front(), None);
+        assert_eq!(first.len(), 4);
+        assert_eq!(first.peek_back(), Some(&4));
+
+        first.push_back(5);
+        second.push_back(6);
+        assert_eq!(first.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
 
+        let mut empty = Queue::new();
+        empty.append(&mut first);
+        empty.append(&mut Queue::new());
+        assert_eq!(empty.peek_front(), Some(&1));
+        assert_eq!(empty.peek_back(), Some(&5));
+        assert_eq!(second.pop_front(), Some(6));
     }
 }
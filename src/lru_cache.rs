@@ -0,0 +1,464 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+
+use crate::doubly_linked_list::{self, DList, NodeHandle};
+
+// Least recently used cache. Entries are kept in a DList in recency order: the
+// front is the most recently used entry and the back is the next one to be
+// evicted. A HashMap from key to the entry's node handle finds it without walking
+// the list, and since the list is doubly linked the node can then be unhooked from
+// the middle and moved to the front in O(1).
+//
+// The unsafe *_by_handle calls below rely on every handle in the map pointing at
+// a node of our own list. That can't depend on K's Hash, Eq and Clone agreeing
+// with each other, since those are safe code that can do anything. So a node is
+// only ever freed after unmap has made sure no map entry points at it any more,
+// checking by handle rather than trusting a lookup by key. A key that misbehaves
+// can leave an entry unreachable, but never a handle to a freed node.
+pub struct LruCache<K, V> {
+    map: HashMap<K, NodeHandle<(K, V)>>,
+    list: DList<(K, V)>,
+    capacity: usize,
+    on_evict: Option<Box<dyn FnMut(K, V)>>,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    // Panics if capacity is 0
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "LruCache capacity must be non-zero");
+
+        LruCache {
+            map: HashMap::with_capacity(capacity),
+            list: DList::new(),
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    // The callback receives every entry the cache throws out to stay within its
+    // capacity, either from put or from shrinking with resize. Entries taken out
+    // with pop_lru, remove or clear are handed back to the caller instead.
+    pub fn with_eviction_callback<F>(capacity: usize, callback: F) -> Self
+    where
+        F: FnMut(K, V) + 'static,
+    {
+        let mut cache = Self::new(capacity);
+        cache.on_evict = Some(Box::new(callback));
+        cache
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    // Looks up a key and marks it as the most recently used entry
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.map.get(key)?;
+        // SAFETY: handles in the map always point into self.list
+        unsafe {
+            self.list.move_to_front_by_handle(handle);
+            Some(&self.list.get_by_handle(handle).1)
+        }
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.map.get(key)?;
+        // SAFETY: handles in the map always point into self.list
+        unsafe {
+            self.list.move_to_front_by_handle(handle);
+            Some(&mut self.list.get_mut_by_handle(handle).1)
+        }
+    }
+
+    // Looks up a key without changing its place in the recency order
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.map.get(key)?;
+        // SAFETY: handles in the map always point into self.list
+        unsafe { Some(&self.list.get_by_handle(handle).1) }
+    }
+
+    // The entry that would be evicted next
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self.list.back().map(|(key, value)| (key, value))
+    }
+
+    // Inserts or updates a key and makes it the most recently used entry.
+    // Returns the old value when the key was already present. If the cache is
+    // full, the least recently used entry is evicted to make room.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&handle) = self.map.get(&key) {
+            // SAFETY: handles in the map always point into self.list
+            unsafe {
+                self.list.move_to_front_by_handle(handle);
+                let entry = self.list.get_mut_by_handle(handle);
+                return Some(std::mem::replace(&mut entry.1, value));
+            }
+        }
+
+        if self.list.len() >= self.capacity {
+            self.evict_lru();
+        }
+
+        let handle = self.list.push_front_handle((key.clone(), value));
+        if let Some(displaced) = self.map.insert(key, handle) {
+            // only possible if the lookup above missed a key the insert then found,
+            // which a well behaved key never does. Drop the entry the map lost
+            // track of, so every node in the list still has a handle in the map
+            // SAFETY: displaced was in the map, so its node is in the list, and
+            // it's no longer in the map to be used again
+            unsafe { self.list.remove_by_handle(displaced) };
+        }
+        None
+    }
+
+    // Removes and returns the least recently used entry
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let back = self.list.back_handle()?;
+        self.unmap(back);
+        // SAFETY: back was just taken from the list, and unmap has removed every
+        // map entry pointing at it
+        Some(unsafe { self.list.remove_by_handle(back) })
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.map.remove(key)?;
+        // SAFETY: the handle came out of the map, so its node is still in the list,
+        // and it's no longer in the map to be used again
+        unsafe { Some(self.list.remove_by_handle(handle).1) }
+    }
+
+    // Changes the capacity, evicting least recently used entries if the cache
+    // now holds too many. Panics if capacity is 0
+    pub fn resize(&mut self, capacity: usize) {
+        assert!(capacity > 0, "LruCache capacity must be non-zero");
+
+        self.capacity = capacity;
+        while self.list.len() > self.capacity {
+            self.evict_lru();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    // Iterates from the most recently used entry to the least recently used one
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.list.iter(),
+        }
+    }
+
+    // Takes the map entry for the node at handle out of the map. Looking it up by
+    // the key stored in the node finds it as long as K behaves, and if it doesn't,
+    // the map is scanned for the handle instead. Handle must be in the list
+    fn unmap(&mut self, handle: NodeHandle<(K, V)>) {
+        // SAFETY: the caller guarantees handle is in the list
+        let key = unsafe { &self.list.get_by_handle(handle).0 };
+        match self.map.remove_entry(key) {
+            Some((_, found)) if found == handle => return,
+            // found belongs to another key that compares equal to this one; it's
+            // still in the list, so put it back
+            Some((other_key, found)) => {
+                if let Some(displaced) = self.map.insert(other_key, found) {
+                    if displaced != handle {
+                        // SAFETY: displaced was in the map and isn't any more
+                        unsafe { self.list.remove_by_handle(displaced) };
+                    }
+                }
+            }
+            None => {}
+        }
+        self.map.retain(|_, found| *found != handle);
+    }
+
+    fn evict_lru(&mut self) {
+        if let Some((key, value)) = self.pop_lru() {
+            if let Some(callback) = self.on_evict.as_mut() {
+                callback(key, value);
+            }
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for LruCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = self.list.iter().map(|(key, value)| (key, value));
+        f.debug_map().entries(entries).finish()
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    inner: doubly_linked_list::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<'a, K, V> IntoIterator for &'a LruCache<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        Iter {
+            inner: self.list.iter(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // not a glob import, since Borrow::borrow would shadow RefCell::borrow
+    use super::LruCache;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn keys(cache: &LruCache<&'static str, i32>) -> Vec<&'static str> {
+        cache.iter().map(|(k, _)| *k).collect()
+    }
+
+    #[test]
+    fn test_put_get() {
+        let mut cache = LruCache::new(2);
+        assert!(cache.is_empty());
+        assert_eq!(cache.put("a", 1), None);
+        assert_eq!(cache.put("b", 2), None);
+        assert_eq!(cache.get(&"a"), Some(&1));
+
+        // "b" is now the least recently used, so it goes first
+        cache.put("c", 3);
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        assert_eq!(cache.get(&"c"), Some(&3));
+        assert_eq!(cache.len(), 2);
+
+        // updating an existing key returns the old value and doesn't evict
+        assert_eq!(cache.put("a", 10), Some(1));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(keys(&cache), vec!["a", "c"]);
+
+        if let Some(val) = cache.get_mut(&"c") {
+            *val = 30;
+        }
+        assert_eq!(keys(&cache), vec!["c", "a"]);
+        assert_eq!(cache.peek(&"c"), Some(&30));
+    }
+
+    #[test]
+    fn test_peek_keeps_order() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+
+        assert_eq!(cache.peek(&"a"), Some(&1));
+        assert_eq!(cache.peek_lru(), Some((&"a", &1)));
+
+        // peeking didn't promote "a", so it is still the one evicted
+        cache.put("c", 3);
+        assert!(!cache.contains(&"a"));
+        assert!(cache.contains(&"b"));
+    }
+
+    #[test]
+    fn test_pop_and_remove() {
+        let mut cache = LruCache::new(3);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+
+        assert_eq!(cache.pop_lru(), Some(("a", 1)));
+        assert_eq!(cache.remove(&"c"), Some(3));
+        assert_eq!(cache.remove(&"c"), None);
+        assert_eq!(cache.len(), 1);
+
+        cache.put("d", 4);
+        cache.put("e", 5);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(keys(&cache), vec!["e", "d", "b"]);
+
+        assert_eq!(cache.pop_lru(), Some(("b", 2)));
+        assert_eq!(cache.pop_lru(), Some(("d", 4)));
+        assert_eq!(cache.pop_lru(), Some(("e", 5)));
+        assert_eq!(cache.pop_lru(), None);
+        assert_eq!(cache.peek_lru(), None);
+    }
+
+    #[test]
+    fn test_resize_and_callback() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&evicted);
+        let mut cache =
+            LruCache::with_eviction_callback(3, move |k: &'static str, v: i32| {
+                log.borrow_mut().push((k, v))
+            });
+
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+        cache.put("d", 4);
+        assert_eq!(*evicted.borrow(), vec![("a", 1)]);
+
+        cache.get(&"b");
+        cache.resize(1);
+        assert_eq!(cache.capacity(), 1);
+        assert_eq!(*evicted.borrow(), vec![("a", 1), ("c", 3), ("d", 4)]);
+        assert_eq!(keys(&cache), vec!["b"]);
+
+        // explicit removal doesn't count as an eviction
+        cache.pop_lru();
+        assert_eq!(evicted.borrow().len(), 3);
+
+        cache.resize(2);
+        cache.put("x", 1);
+        cache.put("y", 2);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(evicted.borrow().len(), 3);
+    }
+
+    #[test]
+    fn test_iter_order() {
+        let mut cache = LruCache::new(4);
+        for (i, key) in ["a", "b", "c", "d"].iter().enumerate() {
+            cache.put(*key, i as i32);
+        }
+        cache.get(&"b");
+        cache.put("c", 20);
+
+        assert_eq!(keys(&cache), vec!["c", "b", "d", "a"]);
+        assert_eq!(cache.iter().len(), 4);
+        assert_eq!(format!("{:?}", cache), r#"{"c": 20, "b": 1, "d": 3, "a": 0}"#);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.iter().next(), None);
+    }
+
+    #[test]
+    fn test_borrowed_lookup() {
+        let mut cache: LruCache<String, usize> = LruCache::new(2);
+        cache.put(String::from("hello"), 5);
+        assert_eq!(cache.get("hello"), Some(&5));
+        assert_eq!(cache.remove("hello"), Some(5));
+    }
+
+    // A key whose clones don't equal the original, so the cache can't find an
+    // entry again by the copy of the key it keeps in the list
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct BadClone(u32);
+
+    impl Clone for BadClone {
+        fn clone(&self) -> Self {
+            BadClone(self.0 + 100)
+        }
+    }
+
+    // A key that hashes differently every time, so HashMap lookups are hit and miss
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Unstable(u32);
+
+    impl std::hash::Hash for Unstable {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            use std::sync::atomic::{AtomicU64, Ordering};
+            static CALLS: AtomicU64 = AtomicU64::new(0);
+            state.write_u64(CALLS.fetch_add(1, Ordering::Relaxed));
+        }
+    }
+
+    #[test]
+    fn test_misbehaving_keys() {
+        // misbehaving keys can make entries unreachable, but the cache has to stay
+        // within capacity and never hold on to an entry it has dropped
+        let mut cache = LruCache::new(1);
+        cache.put(BadClone(1), 1);
+        cache.put(BadClone(2), 2);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.peek(&BadClone(1)), None);
+        assert_eq!(cache.peek(&BadClone(2)), Some(&2));
+        assert_eq!(cache.pop_lru(), Some((BadClone(102), 2)));
+        assert!(cache.is_empty());
+        assert_eq!(cache.peek(&BadClone(2)), None);
+
+        let mut cache = LruCache::new(3);
+        for i in 0..200 {
+            cache.put(BadClone(i % 7), i);
+            cache.get(&BadClone((i + 3) % 7));
+            if i % 5 == 0 {
+                cache.remove(&BadClone(i % 7));
+            }
+            assert!(cache.len() <= 3);
+            assert_eq!(cache.iter().count(), cache.len());
+        }
+
+        let mut cache = LruCache::new(3);
+        for i in 0..200 {
+            cache.put(Unstable(i % 5), i);
+            if let Some(val) = cache.get_mut(&Unstable((i + 1) % 5)) {
+                *val += 1;
+            }
+            cache.peek(&Unstable(i % 5));
+            if i % 7 == 0 {
+                cache.remove(&Unstable(i % 5));
+            }
+            if i % 11 == 0 {
+                cache.pop_lru();
+            }
+            assert!(cache.len() <= 3);
+            assert_eq!(cache.iter().count(), cache.len());
+        }
+        cache.resize(1);
+        assert!(cache.len() <= 1);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_capacity() {
+        let _cache: LruCache<i32, i32> = LruCache::new(0);
+    }
+}
@@ -1,6 +1,8 @@
 extern crate rand;
 // use rand::Rng;
 use rand::distributions::{Distribution, Uniform};
+use rand::rngs::StdRng;
+use rand::{FromEntropy, Rng, SeedableRng};
 
 pub fn gen_random_vector(start: i32, stop: i32, count: u32) -> Vec<i32> {
     let step = Uniform::new(start, stop);
@@ -10,6 +12,29 @@ pub fn gen_random_vector(start: i32, stop: i32, count: u32) -> Vec<i32> {
     choices
 }
 
+// Random number generator for randomized data structures like skip_list::SkipList.
+// The same seed always produces the same sequence, which keeps test runs deterministic
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+// Generator seeded from the operating system for when repeatability doesn't matter
+pub fn entropy_rng() -> StdRng {
+    StdRng::from_entropy()
+}
+
+// Flips a coin that lands heads with probability p and counts how many flips it
+// takes to see tails, capped at max_level. Level 1 comes up half the time for
+// p = 0.5, level 2 a quarter of the time, and so on
+pub fn geometric_level<R: Rng>(rng: &mut R, p: f64, max_level: usize) -> usize {
+    let mut level = 1;
+    while level < max_level && rng.gen_bool(p) {
+        level += 1;
+    }
+
+    level
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,4 +52,22 @@ mod tests {
         
     }
 
+    #[test]
+    fn test_seeded_levels() {
+        let mut rng_a = seeded_rng(42);
+        let mut rng_b = seeded_rng(42);
+
+        let levels_a: Vec<usize> = (0..100).map(|_| geometric_level(&mut rng_a, 0.5, 8)).collect();
+        let levels_b: Vec<usize> = (0..100).map(|_| geometric_level(&mut rng_b, 0.5, 8)).collect();
+
+        // same seed, same levels
+        assert_eq!(levels_a, levels_b);
+        assert!(levels_a.iter().all(|&level| (1..=8).contains(&level)));
+        assert!(levels_a.contains(&1) && levels_a.iter().any(|&level| level > 1));
+
+        let mut rng = entropy_rng();
+        assert_eq!(geometric_level(&mut rng, 0.0, 8), 1);
+        assert_eq!(geometric_level(&mut rng, 1.0, 8), 8);
+    }
+
 }
\ No newline at end of file
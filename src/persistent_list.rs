@@ -0,0 +1,278 @@
+use std::fmt::{self, Debug};
+use std::iter::FromIterator;
+use std::rc::Rc;
+use std::sync::Arc;
+
+// Immutable singly linked lists where every "modification" returns a new list
+// that shares its tail with the old one, so keeping old versions around is cheap.
+// RcList is for single threaded use, ArcList can be shared between threads.
+// Both are generated from the same macro since only the pointer type differs.
+macro_rules! persistent_list {
+    ($list:ident, $node:ident, $iter:ident, $ptr:ident) => {
+        struct $node<T> {
+            data: T,
+            next: Option<$ptr<$node<T>>>,
+        }
+
+        pub struct $list<T> {
+            head: Option<$ptr<$node<T>>>,
+            len: usize,
+        }
+
+        impl<T> $list<T> {
+            pub fn new() -> Self {
+                $list { head: None, len: 0 }
+            }
+
+            pub fn len(&self) -> usize {
+                self.len
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.head.is_none()
+            }
+
+            // Returns a new list with data in front of the nodes of this one.
+            // Self is left untouched and both lists share everything after the new head.
+            pub fn prepend(&self, data: T) -> Self {
+                $list {
+                    head: Some($ptr::new($node {
+                        data,
+                        next: self.head.clone(),
+                    })),
+                    len: self.len + 1,
+                }
+            }
+
+            // Returns the list without its first element, sharing the remaining nodes
+            pub fn tail(&self) -> Self {
+                $list {
+                    head: self.head.as_ref().and_then(|node| node.next.clone()),
+                    len: self.len.saturating_sub(1),
+                }
+            }
+
+            pub fn head(&self) -> Option<&T> {
+                self.head.as_ref().map(|node| &node.data)
+            }
+
+            pub fn iter(&self) -> $iter<'_, T> {
+                $iter {
+                    next: self.head.as_deref(),
+                }
+            }
+        }
+
+        // Dropping the head would recursively drop every node it uniquely owns,
+        // so walk the chain by hand like LinkedList does. As soon as a node is
+        // still shared with another version we stop, since that version keeps
+        // the rest of the chain alive. into_inner rather than try_unwrap matters
+        // for ArcList: if two threads drop the last two handles at once, both
+        // try_unwraps can fail and whichever Arc goes last frees the chain
+        // recursively, but exactly one into_inner is guaranteed to get the node.
+        impl<T> Drop for $list<T> {
+            fn drop(&mut self) {
+                let mut head = self.head.take();
+                while let Some(node) = head {
+                    head = $ptr::into_inner(node).and_then(|mut node| node.next.take());
+                }
+            }
+        }
+
+        // Cloning only bumps the reference count of the head
+        impl<T> Clone for $list<T> {
+            fn clone(&self) -> Self {
+                $list {
+                    head: self.head.clone(),
+                    len: self.len,
+                }
+            }
+        }
+
+        impl<T> Default for $list<T> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<T: Debug> Debug for $list<T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_list().entries(self.iter()).finish()
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for $list<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.len == other.len && self.iter().eq(other.iter())
+            }
+        }
+
+        impl<T: Eq> Eq for $list<T> {}
+
+        // The first item of the iterator becomes the head of the list
+        impl<T> FromIterator<T> for $list<T> {
+            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+                let items: Vec<T> = iter.into_iter().collect();
+                let mut list = $list::new();
+                for data in items.into_iter().rev() {
+                    list = list.prepend(data);
+                }
+                list
+            }
+        }
+
+        pub struct $iter<'a, T> {
+            next: Option<&'a $node<T>>,
+        }
+
+        impl<'a, T> Iterator for $iter<'a, T> {
+            type Item = &'a T;
+            fn next(&mut self) -> Option<Self::Item> {
+                self.next.map(|node| {
+                    self.next = node.next.as_deref();
+                    &node.data
+                })
+            }
+        }
+
+        impl<'a, T> IntoIterator for &'a $list<T> {
+            type Item = &'a T;
+            type IntoIter = $iter<'a, T>;
+
+            fn into_iter(self) -> $iter<'a, T> {
+                self.iter()
+            }
+        }
+    };
+}
+
+persistent_list!(RcList, RcNode, RcIter, Rc);
+persistent_list!(ArcList, ArcNode, ArcIter, Arc);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Barrier;
+    use std::thread;
+
+    #[test]
+    fn test_prepend_and_tail() {
+        let list = RcList::new();
+        assert_eq!(list.head(), None);
+        assert!(list.is_empty());
+
+        let list = list.prepend(1).prepend(2).prepend(3);
+        assert_eq!(list.head(), Some(&3));
+        assert_eq!(list.len(), 3);
+
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&2));
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&1));
+        let list = list.tail();
+        assert_eq!(list.head(), None);
+
+        // tail of an empty list is still empty
+        let list = list.tail();
+        assert_eq!(list.head(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn test_iter() {
+        let list = RcList::new().prepend(1).prepend(2).prepend(3);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), None);
+
+        let collected: RcList<i32> = vec![3, 2, 1].into_iter().collect();
+        assert_eq!(collected, list);
+        assert_eq!(format!("{:?}", collected), "[3, 2, 1]");
+    }
+
+    #[test]
+    fn test_versions_share_tails() {
+        let base = RcList::new().prepend(1).prepend(2);
+        let left = base.prepend(10);
+        let right = base.prepend(20);
+
+        // both versions point at the very same nodes after their heads
+        let left_tail = left.tail();
+        let right_tail = right.tail();
+        assert!(std::ptr::eq(left_tail.head().unwrap(), right_tail.head().unwrap()));
+        assert!(std::ptr::eq(base.head().unwrap(), left_tail.head().unwrap()));
+
+        // dropping one version leaves the others intact
+        drop(base);
+        drop(left);
+        assert_eq!(right.iter().copied().collect::<Vec<_>>(), vec![20, 2, 1]);
+        assert_eq!(right.clone(), right);
+    }
+
+    #[test]
+    fn test_long_list_drop() {
+        let mut list = RcList::new();
+        for i in 0..200_000 {
+            list = list.prepend(i);
+        }
+        let snapshot = list.tail().tail();
+        drop(list);
+        assert_eq!(snapshot.len(), 199_998);
+        drop(snapshot);
+
+        let mut shared = ArcList::new();
+        for i in 0..200_000 {
+            shared = shared.prepend(i);
+        }
+        assert_eq!(shared.head(), Some(&199_999));
+    }
+
+    #[test]
+    fn test_arc_list_concurrent_drop() {
+        // two threads letting go of the last two handles to a long list at the
+        // same moment, so neither can tell it's the last owner beforehand
+        for _ in 0..10 {
+            let list: ArcList<u32> = (0..200_000).collect();
+            let barrier = Arc::new(Barrier::new(2));
+
+            let handles: Vec<_> = vec![list.clone(), list]
+                .into_iter()
+                .map(|handle| {
+                    let barrier = Arc::clone(&barrier);
+                    thread::spawn(move || {
+                        barrier.wait();
+                        drop(handle);
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_arc_list_across_threads() {
+        let base: ArcList<u32> = (0..100).collect();
+
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let snapshot = base.clone();
+                thread::spawn(move || {
+                    let mine = snapshot.prepend(1000 + i);
+                    mine.iter().sum::<u32>()
+                })
+            })
+            .collect();
+
+        let base_sum: u32 = base.iter().sum();
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.join().unwrap(), base_sum + 1000 + i as u32);
+        }
+        assert_eq!(base.len(), 100);
+    }
+}
@@ -1,27 +1,1040 @@
 use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
 
 // Binary search function for sorted vector of integers.
 // If the vector is not sorted, this will not work.
 // Rust also has built-in binary_search(&self, x: &T) -> Result<usize, usize> vector method
-pub fn binary_search(arr: Vec<i32>, mut left: u32, mut right: u32, target: i32) -> Option<usize> {
-    while left <= right {
-        let mid_idx = left + (right - left) / 2;
+// left and right are inclusive bounds; a right bound past the end of the vector is
+// clamped to the last index, and an empty or inverted range finds nothing
+pub fn binary_search(arr: Vec<i32>, left: u32, right: u32, target: i32) -> Option<usize> {
+    let left = left as usize;
+    let right = (right as usize).min(arr.len().checked_sub(1)?);
+    if left > right {
+        return None;
+    }
+
+    // search the sub-slice and shift the answer back to an index into arr
+    binary_search_by(&arr[left..=right], |probe| probe.cmp(&target))
+        .ok()
+        .map(|idx| idx + left)
+}
+
+// Everything below works on any sorted slice. The searches keep a half-open
+// window [left, right) so the bounds never have to step below zero, and the
+// midpoint is computed as left + (right - left) / 2 so it can't overflow.
+//
+// Like std, a failed search returns Err(idx) where idx is the position the
+// target could be inserted at to keep the slice sorted. Unlike std, a search
+// that hits a run of equal elements always reports the first one.
+
+// Returns the index of the first element the predicate is false for, assuming the
+// slice is partitioned: every element the predicate holds for comes before every
+// element it doesn't. Returns slice.len() if it holds for everything
+pub fn partition_point<T, P>(slice: &[T], mut pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    let mut left = 0;
+    let mut right = slice.len();
+
+    while left < right {
+        let mid = left + (right - left) / 2;
+
+        if pred(&slice[mid]) {
+            left = mid + 1;
+        } else {
+            right = mid;
+        }
+    }
+
+    left
+}
+
+// compare says how each element orders relative to the target being looked for
+pub fn binary_search_by<T, F>(slice: &[T], mut compare: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let idx = lower_bound_by(slice, &mut compare);
+
+    match slice.get(idx) {
+        Some(val) if compare(val) == Ordering::Equal => Ok(idx),
+        _ => Err(idx),
+    }
+}
+
+// Searches a slice sorted by the key that key_fn pulls out of each element
+pub fn binary_search_by_key<T, B, F>(slice: &[T], key: &B, mut key_fn: F) -> Result<usize, usize>
+where
+    B: Ord,
+    F: FnMut(&T) -> B,
+{
+    binary_search_by(slice, |val| key_fn(val).cmp(key))
+}
+
+// Index of the first element that is not less than target
+pub fn lower_bound<T: Ord>(slice: &[T], target: &T) -> usize {
+    lower_bound_by(slice, |val| val.cmp(target))
+}
+
+pub fn lower_bound_by<T, F>(slice: &[T], mut compare: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(slice, |val| compare(val) == Ordering::Less)
+}
+
+pub fn lower_bound_by_key<T, B, F>(slice: &[T], key: &B, mut key_fn: F) -> usize
+where
+    B: Ord,
+    F: FnMut(&T) -> B,
+{
+    lower_bound_by(slice, |val| key_fn(val).cmp(key))
+}
+
+// Index of the first element that is greater than target
+pub fn upper_bound<T: Ord>(slice: &[T], target: &T) -> usize {
+    upper_bound_by(slice, |val| val.cmp(target))
+}
+
+pub fn upper_bound_by<T, F>(slice: &[T], mut compare: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(slice, |val| compare(val) != Ordering::Greater)
+}
+
+pub fn upper_bound_by_key<T, B, F>(slice: &[T], key: &B, mut key_fn: F) -> usize
+where
+    B: Ord,
+    F: FnMut(&T) -> B,
+{
+    upper_bound_by(slice, |val| key_fn(val).cmp(key))
+}
+
+// The range of indices holding elements equal to target. The range is empty
+// (and starts at the insertion point) when target isn't in the slice
+pub fn equal_range<T: Ord>(slice: &[T], target: &T) -> Range<usize> {
+    equal_range_by(slice, |val| val.cmp(target))
+}
+
+pub fn equal_range_by<T, F>(slice: &[T], mut compare: F) -> Range<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let start = lower_bound_by(slice, &mut compare);
+    // the upper bound can only be at or after the lower bound
+    let end = start + upper_bound_by(&slice[start..], &mut compare);
+
+    start..end
+}
+
+pub fn equal_range_by_key<T, B, F>(slice: &[T], key: &B, mut key_fn: F) -> Range<usize>
+where
+    B: Ord,
+    F: FnMut(&T) -> B,
+{
+    equal_range_by(slice, |val| key_fn(val).cmp(key))
+}
+
+// Common interface for the different ways of searching a sorted slice, so callers
+// (and the differential tests) can swap algorithms. Every strategy returns the
+// index of an element equal to target, or None. When target appears more than
+// once, different strategies may land on different copies of it.
+pub trait SearchStrategy<T: Ord> {
+    fn name(&self) -> &'static str;
+
+    fn search(&self, slice: &[T], target: &T) -> Option<usize>;
+}
+
+// Plain binary search, via binary_search_by
+pub struct BinarySearch;
+
+// Doubles a bound until it passes target, then binary searches the last doubling.
+// O(log i) where i is target's position, so it's quick for targets near the front
+// and for slices whose length isn't known up front
+pub struct ExponentialSearch;
+
+// Guesses target's position from its value, assuming values are spread out
+// roughly evenly. O(log log n) on uniform data but O(n) in the worst case
+pub struct InterpolationSearch;
+
+// Splits the window into thirds instead of halves. O(log n) but with more
+// comparisons than binary search; mostly here for comparison
+pub struct TernarySearch;
+
+// Jumps ahead sqrt(n) elements at a time, then scans the block that must hold
+// target. O(sqrt n), and only ever steps backwards within one block
+pub struct JumpSearch;
+
+// Splits the window at Fibonacci numbers rather than halves, so the probe
+// positions only need addition and subtraction. O(log n)
+pub struct FibonacciSearch;
+
+impl<T: Ord> SearchStrategy<T> for BinarySearch {
+    fn name(&self) -> &'static str {
+        "binary"
+    }
+
+    fn search(&self, slice: &[T], target: &T) -> Option<usize> {
+        binary_search_by(slice, |val| val.cmp(target)).ok()
+    }
+}
+
+impl<T: Ord> SearchStrategy<T> for ExponentialSearch {
+    fn name(&self) -> &'static str {
+        "exponential"
+    }
+
+    fn search(&self, slice: &[T], target: &T) -> Option<usize> {
+        // grow the bound until slice[bound] is at least target or we run off the end
+        let mut bound = 1;
+        while bound < slice.len() && slice[bound] < *target {
+            bound = bound.saturating_mul(2);
+        }
+
+        // target has to be between the previous bound and this one
+        let start = bound / 2;
+        let end = bound.saturating_add(1).min(slice.len());
+        binary_search_by(&slice[start..end], |val| val.cmp(target))
+            .ok()
+            .map(|idx| idx + start)
+    }
+}
+
+// Values interpolation search can turn into a rough position
+pub trait Interpolate: Ord {
+    fn as_f64(&self) -> f64;
+}
+
+macro_rules! impl_interpolate {
+    ($($int:ty),*) => {
+        $(impl Interpolate for $int {
+            fn as_f64(&self) -> f64 {
+                *self as f64
+            }
+        })*
+    };
+}
+
+impl_interpolate!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl<T: Interpolate> SearchStrategy<T> for InterpolationSearch {
+    fn name(&self) -> &'static str {
+        "interpolation"
+    }
+
+    fn search(&self, slice: &[T], target: &T) -> Option<usize> {
+        // inclusive window, only entered when it isn't empty
+        let mut low = 0;
+        let mut high = slice.len().checked_sub(1)?;
+
+        while low <= high && *target >= slice[low] && *target <= slice[high] {
+            if slice[low] == slice[high] {
+                // every value in the window is the same, so it's this one or nothing
+                return if slice[low] == *target {
+                    Some(low)
+                } else {
+                    None
+                };
+            }
+
+            // estimate how far along the window target sits from its value. Doing the
+            // math in f64 avoids overflow, and clamping covers any rounding error
+            let low_val = slice[low].as_f64();
+            let fraction = (target.as_f64() - low_val) / (slice[high].as_f64() - low_val);
+            let guess = low + (fraction * (high - low) as f64) as usize;
+            let guess = guess.clamp(low, high);
+
+            match slice[guess].cmp(target) {
+                Ordering::Equal => return Some(guess),
+                Ordering::Less => low = guess + 1,
+                Ordering::Greater => high = guess.checked_sub(1)?,
+            }
+        }
+
+        None
+    }
+}
+
+impl<T: Ord> SearchStrategy<T> for TernarySearch {
+    fn name(&self) -> &'static str {
+        "ternary"
+    }
+
+    fn search(&self, slice: &[T], target: &T) -> Option<usize> {
+        let mut left = 0;
+        let mut right = slice.len();
+
+        while left < right {
+            let third = (right - left) / 3;
+            let mid1 = left + third;
+            let mid2 = right - 1 - third;
+
+            match target.cmp(&slice[mid1]) {
+                Ordering::Equal => return Some(mid1),
+                // target is in the first third
+                Ordering::Less => {
+                    right = mid1;
+                    continue;
+                }
+                Ordering::Greater => {}
+            }
+
+            match target.cmp(&slice[mid2]) {
+                Ordering::Equal => return Some(mid2),
+                // target is in the last third
+                Ordering::Greater => left = mid2 + 1,
+                // otherwise it's in the middle third
+                Ordering::Less => {
+                    left = mid1 + 1;
+                    right = mid2;
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<T: Ord> SearchStrategy<T> for JumpSearch {
+    fn name(&self) -> &'static str {
+        "jump"
+    }
+
+    fn search(&self, slice: &[T], target: &T) -> Option<usize> {
+        let step = ((slice.len() as f64).sqrt() as usize).max(1);
+
+        let mut start = 0;
+        while start < slice.len() {
+            let end = (start + step).min(slice.len());
+
+            // the first block whose last element reaches target is the only one it can be in
+            if slice[end - 1] >= *target {
+                return slice[start..end]
+                    .iter()
+                    .position(|val| val == target)
+                    .map(|idx| idx + start);
+            }
+
+            start = end;
+        }
+
+        None
+    }
+}
+
+impl<T: Ord> SearchStrategy<T> for FibonacciSearch {
+    fn name(&self) -> &'static str {
+        "fibonacci"
+    }
+
+    fn search(&self, slice: &[T], target: &T) -> Option<usize> {
+        let len = slice.len();
+
+        // find the smallest Fibonacci number that is at least len, keeping the two
+        // before it around as well
+        let mut fib_2 = 0;
+        let mut fib_1 = 1;
+        let mut fib = 1;
+        while fib < len {
+            fib_2 = fib_1;
+            fib_1 = fib;
+            fib = fib_1 + fib_2;
+        }
+
+        // number of elements at the front already known to be less than target
+        let mut eliminated = 0;
+        while fib > 1 {
+            // fib_2 is at least 1 here since fib_2, fib_1, fib are consecutive Fibonacci numbers
+            let probe = (eliminated + fib_2 - 1).min(len - 1);
+
+            match slice[probe].cmp(target) {
+                // drop everything up to the probe and step down one Fibonacci number
+                Ordering::Less => {
+                    fib = fib_1;
+                    fib_1 = fib_2;
+                    fib_2 = fib - fib_1;
+                    eliminated = probe + 1;
+                }
+                // keep only what's before the probe and step down two Fibonacci numbers
+                Ordering::Greater => {
+                    fib = fib_2;
+                    fib_1 -= fib_2;
+                    fib_2 = fib - fib_1;
+                }
+                Ordering::Equal => return Some(probe),
+            }
+        }
+
+        // one element can be left over that the loop never probed
+        match slice.get(eliminated) {
+            Some(val) if val == target => Some(eliminated),
+            _ => None,
+        }
+    }
+}
+
+// Search on the answer: instead of looking through a slice, look for the smallest
+// value x such that some monotonic predicate holds, i.e. pred is false for every
+// value below x and true from x onwards.
+
+// Integer types first_true can search over. The midpoint is computed bitwise as
+// (a & b) + ((a ^ b) >> 1), which rounds down and can't overflow for any pair of
+// values, signed or unsigned, so the whole range of the type is usable
+pub trait SearchInt: Copy + Ord {
+    fn midpoint(self, other: Self) -> Self;
+
+    fn succ(self) -> Self;
+}
+
+macro_rules! impl_search_int {
+    ($($int:ty),*) => {
+        $(impl SearchInt for $int {
+            fn midpoint(self, other: Self) -> Self {
+                (self & other) + ((self ^ other) >> 1)
+            }
+
+            fn succ(self) -> Self {
+                self + 1
+            }
+        })*
+    };
+}
+
+impl_search_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+// Returns the smallest x in range that pred holds for, or None if it holds for none
+// of them. Only calls pred O(log(hi - lo)) times
+pub fn first_true<T, P>(range: Range<T>, mut pred: P) -> Option<T>
+where
+    T: SearchInt,
+    P: FnMut(T) -> bool,
+{
+    let Range {
+        start: mut low,
+        end,
+    } = range;
+    let mut high = end;
+
+    // invariant: pred is false below low and true from high onwards (treating end as true)
+    while low < high {
+        let mid = low.midpoint(high);
+
+        if pred(mid) {
+            high = mid;
+        } else {
+            // mid < high, so this can't overflow
+            low = mid.succ();
+        }
+    }
+
+    // an empty or inverted range ends up here too
+    if low >= end {
+        None
+    } else {
+        Some(low)
+    }
+}
+
+// When bisect should stop. It stops once the interval it's narrowing is no wider
+// than abs or rel times the magnitude of its ends, whichever is larger, and gives
+// up after max_iterations halvings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub abs: f64,
+    pub rel: f64,
+    pub max_iterations: u32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            abs: 1e-9,
+            rel: 1e-9,
+            max_iterations: 200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BisectError {
+    // low or high is NaN or infinite, or low > high
+    InvalidRange,
+    // pred doesn't hold at high, so there's no answer in the range
+    NeverTrue,
+    // ran out of iterations; pred is false at low and true at high
+    NotConverged { low: f64, high: f64 },
+}
+
+impl fmt::Display for BisectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BisectError::InvalidRange => write!(f, "invalid bisection range"),
+            BisectError::NeverTrue => write!(f, "predicate is false over the whole range"),
+            BisectError::NotConverged { low, high } => {
+                write!(
+                    f,
+                    "bisection did not converge, answer is in ({}, {}]",
+                    low, high
+                )
+            }
+        }
+    }
+}
+
+impl Error for BisectError {}
+
+// Float version of first_true: narrows in on the point in [low, high] where pred
+// switches from false to true and returns a value pred holds for that is within
+// tolerance of that point
+pub fn bisect<P>(low: f64, high: f64, mut pred: P, tolerance: Tolerance) -> Result<f64, BisectError>
+where
+    P: FnMut(f64) -> bool,
+{
+    // infinite ends can't be halved towards, so the width check and the midpoint
+    // would both give up straight away and return high without ever searching
+    if !low.is_finite() || !high.is_finite() || low > high {
+        return Err(BisectError::InvalidRange);
+    }
+    if !pred(high) {
+        return Err(BisectError::NeverTrue);
+    }
+    if pred(low) {
+        return Ok(low);
+    }
+
+    // invariant: pred(low) is false and pred(high) is true
+    let mut low = low;
+    let mut high = high;
+    for _ in 0..tolerance.max_iterations {
+        let width = high - low;
+        let scale = low.abs().max(high.abs());
+        if width <= tolerance.abs.max(tolerance.rel * scale) {
+            return Ok(high);
+        }
+
+        // halving each end separately means huge ranges like f64::MIN..f64::MAX
+        // can't overflow to infinity
+        let mid = low / 2.0 + high / 2.0;
+        if mid <= low || mid >= high {
+            // low and high are neighbouring floats, so this is as close as it gets
+            return Ok(high);
+        }
+
+        if pred(mid) {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+
+    Err(BisectError::NotConverged { low, high })
+}
+
+// Searching slices that are sorted, but not in one piece. A rotated slice is a
+// sorted slice with its back end moved to the front, like vectors::rotate_array
+// produces: [5, 6, 1, 2, 3, 4]. A bitonic slice rises then falls: [1, 4, 6, 5, 2].
+//
+// The plain versions need the values to be distinct (strictly increasing then
+// strictly decreasing for bitonic) and always run in O(log n). The _dups
+// versions accept repeated values, but a run of equal values can hide which side
+// of the break the search is on, so they fall back to looking at both sides and
+// degrade to O(n) in the worst case (e.g. [1, 1, 1, 0, 1, 1, 1, 1]).
+
+// Returns the index the sorted order starts at, i.e. the position of the smallest
+// element, or 0 if the slice isn't rotated at all (or is empty). Values must be distinct
+pub fn find_rotation_point<T: Ord>(slice: &[T]) -> usize {
+    if slice.is_empty() {
+        return 0;
+    }
+
+    let mut low = 0;
+    let mut high = slice.len() - 1;
+
+    // the break is somewhere in [low, high]
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if slice[mid] > slice[high] {
+            // mid is still on the larger, front part
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    low
+}
+
+// find_rotation_point for slices that may repeat values. O(log n) unless it hits
+// runs of equal values, O(n) worst case
+pub fn find_rotation_point_dups<T: Ord>(slice: &[T]) -> usize {
+    if slice.is_empty() {
+        return 0;
+    }
+
+    let mut low = 0;
+    let mut high = slice.len() - 1;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        match slice[mid].cmp(&slice[high]) {
+            Ordering::Greater => low = mid + 1,
+            Ordering::Less => high = mid,
+            // can't tell which side mid is on, so shrink the window by one. high
+            // is only safe to drop if it isn't the start of the sorted order itself
+            Ordering::Equal => {
+                if slice[high - 1] > slice[high] {
+                    return high;
+                }
+                high -= 1;
+            }
+        }
+    }
+
+    low
+}
+
+// Both halves either side of the rotation point are sorted, so search each one
+fn search_around<T: Ord>(slice: &[T], target: &T, pivot: usize) -> Option<usize> {
+    let (front, back) = slice.split_at(pivot);
+
+    if let Ok(idx) = binary_search_by(back, |val| val.cmp(target)) {
+        return Some(idx + pivot);
+    }
+    binary_search_by(front, |val| val.cmp(target)).ok()
+}
+
+// Returns the index of target in a rotated slice of distinct values, O(log n)
+pub fn search_rotated<T: Ord>(slice: &[T], target: &T) -> Option<usize> {
+    search_around(slice, target, find_rotation_point(slice))
+}
+
+// Returns the index of target in a rotated slice that may repeat values. O(n)
+// worst case because of find_rotation_point_dups
+pub fn search_rotated_dups<T: Ord>(slice: &[T], target: &T) -> Option<usize> {
+    search_around(slice, target, find_rotation_point_dups(slice))
+}
+
+// Returns the index of a local maximum: an element no smaller than its neighbours.
+// There's always at least one in a non-empty slice, and walking uphill from the
+// midpoint always reaches one, so this is O(log n) on any slice, duplicates
+// included. Only None for an empty slice
+pub fn find_peak<T: Ord>(slice: &[T]) -> Option<usize> {
+    let mut low = 0;
+    let mut high = slice.len().checked_sub(1)?;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if slice[mid] < slice[mid + 1] {
+            // uphill to the right, so there's a peak after mid
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    Some(low)
+}
+
+// Index of the largest element of a bitonic slice that may repeat values. When
+// two neighbours are equal there's no telling which side of the top they're on,
+// so both halves are searched. O(n) worst case
+fn bitonic_max_dups<T: Ord>(slice: &[T]) -> usize {
+    let mut low = 0;
+    let mut high = slice.len() - 1;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        match slice[mid].cmp(&slice[mid + 1]) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => {
+                // any piece of a bitonic slice is bitonic too
+                let left = low + bitonic_max_dups(&slice[low..=mid]);
+                let right = mid + 1 + bitonic_max_dups(&slice[mid + 1..=high]);
+                return if slice[right] > slice[left] {
+                    right
+                } else {
+                    left
+                };
+            }
+        }
+    }
+
+    low
+}
+
+// The rising part is sorted ascending and the falling part descending
+fn search_bitonic_around<T: Ord>(slice: &[T], target: &T, top: usize) -> Option<usize> {
+    let (rising, falling) = slice.split_at(top + 1);
+
+    if let Ok(idx) = binary_search_by(rising, |val| val.cmp(target)) {
+        return Some(idx);
+    }
+    binary_search_by(falling, |val| target.cmp(val))
+        .ok()
+        .map(|idx| idx + top + 1)
+}
+
+// Returns the index of target in a slice that strictly increases then strictly
+// decreases. In that case the only local maximum is the top, so find_peak finds
+// it and the whole search is O(log n)
+pub fn search_bitonic<T: Ord>(slice: &[T], target: &T) -> Option<usize> {
+    let top = find_peak(slice)?;
+    search_bitonic_around(slice, target, top)
+}
+
+// search_bitonic for a slice that never decreases then never increases, so runs
+// of equal values are allowed. O(n) worst case because of the flat runs
+pub fn search_bitonic_dups<T: Ord>(slice: &[T], target: &T) -> Option<usize> {
+    if slice.is_empty() {
+        return None;
+    }
+    search_bitonic_around(slice, target, bitonic_max_dups(slice))
+}
+
+// Order statistics without fully sorting. These all rearrange the slice in place.
+//
+// select_nth is introselect: quickselect with a median-of-three pivot, which is
+// O(n) on average, and a budget of pivots proportional to log n. If the budget runs
+// out (the pivots keep landing badly, e.g. on adversarial input), it switches to
+// median-of-medians pivots, which always discard at least ~30% of the window, so
+// the worst case stays O(n). Partitioning is three-way so runs of equal values
+// don't slow it down either.
+
+// Windows this small are just insertion sorted
+const SELECT_CUTOFF: usize = 16;
+
+// Puts the nth smallest element (counting from 0) at index n, with everything
+// before it no greater and everything after it no smaller, and returns it.
+// None if n is out of bounds
+pub fn select_nth<T: Ord>(slice: &mut [T], n: usize) -> Option<&mut T> {
+    select_nth_by(slice, n, T::cmp)
+}
+
+pub fn select_nth_by<T, F>(slice: &mut [T], n: usize, mut compare: F) -> Option<&mut T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if n >= slice.len() {
+        return None;
+    }
+
+    let budget = 2 * log2_ceil(slice.len());
+    select_in(slice, n, &mut compare, budget);
+    Some(&mut slice[n])
+}
+
+// Returns the median, or the lower of the two middle elements for an even length
+// since a generic T can't be averaged. None for an empty slice
+pub fn median<T: Ord>(slice: &mut [T]) -> Option<&mut T> {
+    median_by(slice, T::cmp)
+}
+
+pub fn median_by<T, F>(slice: &mut [T], compare: F) -> Option<&mut T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mid = slice.len().checked_sub(1)? / 2;
+    select_nth_by(slice, mid, compare)
+}
+
+// Moves the k smallest elements to the front of the slice in ascending order and
+// returns them. O(n + k log k). k past the end of the slice takes everything
+pub fn k_smallest<T: Ord>(slice: &mut [T], k: usize) -> &mut [T] {
+    k_smallest_by(slice, k, T::cmp)
+}
+
+pub fn k_smallest_by<T, F>(slice: &mut [T], k: usize, mut compare: F) -> &mut [T]
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let k = k.min(slice.len());
+    if k == 0 {
+        return &mut slice[..0];
+    }
+
+    // everything before k - 1 is now no greater than it, so only those need sorting
+    select_nth_by(slice, k - 1, &mut compare);
+    let smallest = &mut slice[..k];
+    smallest.sort_unstable_by(compare);
+    smallest
+}
+
+// Moves the k largest elements to the front of the slice in descending order and
+// returns them
+pub fn k_largest<T: Ord>(slice: &mut [T], k: usize) -> &mut [T] {
+    k_largest_by(slice, k, T::cmp)
+}
+
+pub fn k_largest_by<T, F>(slice: &mut [T], k: usize, mut compare: F) -> &mut [T]
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    k_smallest_by(slice, k, |a, b| compare(b, a))
+}
+
+// ⌈log2 n⌉, with 0 for n = 0 or 1. Counting the bits of n - 1 rather than going
+// through next_power_of_two means it can't overflow for huge slices of ZSTs
+fn log2_ceil(n: usize) -> usize {
+    (usize::BITS - n.saturating_sub(1).leading_zeros()) as usize
+}
+
+// Narrows in on n. Each pass picks a pivot while the budget lasts, and after that
+// uses median-of-medians. A budget of 0 means median-of-medians from the start
+fn select_in<T, F>(mut slice: &mut [T], mut n: usize, compare: &mut F, mut budget: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    loop {
+        if slice.len() <= SELECT_CUTOFF {
+            insertion_sort_by(slice, compare);
+            return;
+        }
+
+        let pivot = if budget > 0 {
+            budget -= 1;
+            median_of_three(slice, compare)
+        } else {
+            median_of_medians(slice, compare)
+        };
+
+        // [..less) are smaller than the pivot, [less..greater) equal to it
+        let (less, greater) = partition_three_way(slice, pivot, compare);
+
+        if n < less {
+            slice = &mut slice[..less];
+        } else if n < greater {
+            return;
+        } else {
+            slice = &mut slice[greater..];
+            n -= greater;
+        }
+    }
+}
+
+fn insertion_sort_by<T, F>(slice: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..slice.len() {
+        let mut j = i;
+        while j > 0 && compare(&slice[j - 1], &slice[j]) == Ordering::Greater {
+            slice.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+// Index of the median of the first, middle and last elements
+fn median_of_three<T, F>(slice: &[T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let (a, b, c) = (0, slice.len() / 2, slice.len() - 1);
+    let less =
+        |x: usize, y: usize, compare: &mut F| compare(&slice[x], &slice[y]) == Ordering::Less;
+
+    if less(a, b, compare) {
+        if less(b, c, compare) {
+            b
+        } else if less(a, c, compare) {
+            c
+        } else {
+            a
+        }
+    } else if less(a, c, compare) {
+        a
+    } else if less(b, c, compare) {
+        c
+    } else {
+        b
+    }
+}
+
+// Sorts each group of 5, gathers the group medians at the front of the slice and
+// selects the median of those (with median-of-medians again, so it's linear too).
+// Returns the index it ends up at
+fn median_of_medians<T, F>(slice: &mut [T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let groups = slice.len().div_ceil(5);
+
+    for group in 0..groups {
+        let start = group * 5;
+        let end = (start + 5).min(slice.len());
+        insertion_sort_by(&mut slice[start..end], compare);
+        // group <= start, so this only ever swaps into a group already handled
+        slice.swap(group, start + (end - start - 1) / 2);
+    }
+
+    let mid = (groups - 1) / 2;
+    select_in(&mut slice[..groups], mid, compare, 0);
+    mid
+}
+
+// Dutch national flag partition around the element at pivot. Returns (less, greater)
+// where [..less) is smaller than the pivot, [less..greater) equal and [greater..) larger
+fn partition_three_way<T, F>(slice: &mut [T], pivot: usize, compare: &mut F) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // park the pivot at the front so the rest can be shuffled around it
+    slice.swap(0, pivot);
+    let (head, rest) = slice.split_at_mut(1);
+    let pivot = &head[0];
+
+    let mut less = 0;
+    let mut idx = 0;
+    let mut greater = rest.len();
+    while idx < greater {
+        match compare(&rest[idx], pivot) {
+            Ordering::Less => {
+                rest.swap(less, idx);
+                less += 1;
+                idx += 1;
+            }
+            Ordering::Greater => {
+                greater -= 1;
+                rest.swap(idx, greater);
+            }
+            Ordering::Equal => idx += 1,
+        }
+    }
+
+    // rest is offset by one, so slice[less] is the last smaller element (or the
+    // pivot itself if there are none). Swapping the pivot there puts it at the
+    // start of the equal run
+    slice.swap(0, less);
+    (less, greater + 1)
+}
+
+// Read access to a 2D grid, so the matrix searches work the same on a Vec per row
+// and on one flat row-major buffer. Rows are expected to all be the same length;
+// if they aren't the searches won't panic, but they may miss things
+pub trait Matrix<T> {
+    fn rows(&self) -> usize;
+
+    fn cols(&self) -> usize;
+
+    fn get(&self, row: usize, col: usize) -> Option<&T>;
+}
+
+impl<T> Matrix<T> for [Vec<T>] {
+    fn rows(&self) -> usize {
+        self.len()
+    }
+
+    fn cols(&self) -> usize {
+        self.first().map_or(0, Vec::len)
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&T> {
+        <[Vec<T>]>::get(self, row)?.get(col)
+    }
+}
+
+impl<T> Matrix<T> for Vec<Vec<T>> {
+    fn rows(&self) -> usize {
+        self.as_slice().rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.as_slice().cols()
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&T> {
+        Matrix::get(self.as_slice(), row, col)
+    }
+}
+
+// A flat buffer viewed as rows of width elements. A partial row left over at the
+// end of the buffer is ignored
+pub struct FlatMatrix<'a, T> {
+    data: &'a [T],
+    width: usize,
+}
+
+impl<'a, T> FlatMatrix<'a, T> {
+    pub fn new(data: &'a [T], width: usize) -> Self {
+        FlatMatrix { data, width }
+    }
+}
+
+impl<T> Matrix<T> for FlatMatrix<'_, T> {
+    fn rows(&self) -> usize {
+        self.data.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    fn cols(&self) -> usize {
+        self.width
+    }
 
-        let curr_val = arr.get(mid_idx as usize).unwrap();
+    fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row >= self.rows() || col >= self.width {
+            return None;
+        }
+        self.data.get(row * self.width + col)
+    }
+}
 
-        match curr_val.cmp(&target) {
-            Ordering::Less => left = mid_idx + 1,
-            Ordering::Greater => right = mid_idx - 1,
-            Ordering::Equal => return Some(mid_idx as usize),
+// Returns the (row, col) of target in a matrix whose rows and columns are each
+// sorted ascending. Starts in the top right corner: anything bigger than target
+// rules out the rest of its column and anything smaller rules out the rest of its
+// row, so it takes O(rows + cols) steps
+pub fn search_sorted_matrix<T, M>(matrix: &M, target: &T) -> Option<(usize, usize)>
+where
+    T: Ord,
+    M: Matrix<T> + ?Sized,
+{
+    let mut row = 0;
+    let mut col = matrix.cols().checked_sub(1)?;
+
+    while row < matrix.rows() {
+        match matrix.get(row, col)?.cmp(target) {
+            Ordering::Equal => return Some((row, col)),
+            Ordering::Greater => col = col.checked_sub(1)?,
+            Ordering::Less => row += 1,
         }
     }
 
     None
 }
 
+// Returns the (row, col) of target in a matrix that's sorted when read row by row,
+// i.e. each row is sorted and starts no smaller than the previous one ended.
+// Binary searches over the cells as if they were one slice, O(log(rows * cols))
+pub fn search_row_major_sorted<T, M>(matrix: &M, target: &T) -> Option<(usize, usize)>
+where
+    T: Ord,
+    M: Matrix<T> + ?Sized,
+{
+    let cols = matrix.cols();
+    let cells = matrix.rows().checked_mul(cols)?;
+    let cell = |idx: usize| matrix.get(idx / cols, idx % cols);
+
+    // a missing cell (ragged rows) counts as past the target so the search still ends
+    let idx = first_true(0..cells, |idx| cell(idx).is_none_or(|val| val >= target))?;
+    if cell(idx)? == target {
+        Some((idx / cols, idx % cols))
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::gen_random_vector;
+    use crate::vectors::rotate_array;
 
     #[test]
     fn test_binary_search() {
@@ -37,4 +1050,605 @@ mod tests {
         assert_eq!(binary_search(test1.clone(), 0, right_pointer, 17), None);
         assert_eq!(binary_search(test1.clone(), 0, right_pointer, 23), None);
     }
+
+    #[test]
+    fn test_binary_search_edges() {
+        let test1 = vec![3, 5, 7];
+
+        // targets below the first element and empty vectors used to panic
+        assert_eq!(binary_search(test1.clone(), 0, 2, 1), None);
+        assert_eq!(binary_search(vec![], 0, 0, 1), None);
+        assert_eq!(binary_search(test1.clone(), 0, 10, 7), Some(2));
+        assert_eq!(binary_search(test1.clone(), 1, 2, 3), None);
+        assert_eq!(binary_search(test1.clone(), 2, 1, 5), None);
+        assert_eq!(binary_search(test1, 1, 2, 5), Some(1));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let test1 = [1, 2, 2, 2, 5, 8];
+
+        assert_eq!(lower_bound(&test1, &2), 1);
+        assert_eq!(upper_bound(&test1, &2), 4);
+        assert_eq!(equal_range(&test1, &2), 1..4);
+        assert_eq!(equal_range(&test1, &3), 4..4);
+        assert_eq!(lower_bound(&test1, &0), 0);
+        assert_eq!(upper_bound(&test1, &9), 6);
+        assert_eq!(equal_range::<i32>(&[], &1), 0..0);
+
+        assert_eq!(partition_point(&test1, |x| *x < 5), 4);
+        assert_eq!(partition_point(&test1, |_| true), 6);
+        assert_eq!(partition_point(&test1, |_| false), 0);
+    }
+
+    #[test]
+    fn test_search_variants() {
+        let test1 = [1, 2, 2, 2, 5, 8];
+
+        // runs of equal elements report the first one
+        assert_eq!(binary_search_by(&test1, |x| x.cmp(&2)), Ok(1));
+        assert_eq!(binary_search_by(&test1, |x| x.cmp(&6)), Err(5));
+        assert_eq!(binary_search_by(&test1, |x| x.cmp(&0)), Err(0));
+        assert_eq!(binary_search_by(&[] as &[i32], |x| x.cmp(&0)), Err(0));
+
+        let people = [("ann", 21), ("bo", 30), ("cy", 30), ("di", 44)];
+        assert_eq!(binary_search_by_key(&people, &30, |p| p.1), Ok(1));
+        assert_eq!(binary_search_by_key(&people, &35, |p| p.1), Err(3));
+        assert_eq!(lower_bound_by_key(&people, &30, |p| p.1), 1);
+        assert_eq!(upper_bound_by_key(&people, &30, |p| p.1), 3);
+        assert_eq!(equal_range_by_key(&people, &30, |p| p.1), 1..3);
+
+        let words = ["apple", "fig", "kiwi", "pear"];
+        assert_eq!(binary_search_by(&words, |w| w.cmp(&"kiwi")), Ok(2));
+        assert_eq!(lower_bound(&words, &"grape"), 2);
+    }
+
+    #[test]
+    fn test_matches_std() {
+        for _ in 0..50 {
+            let mut test1 = gen_random_vector(-50, 50, 40);
+            test1.sort_unstable();
+
+            for target in -55..55 {
+                let ours = binary_search_by(&test1, |x| x.cmp(&target));
+                let std = test1.binary_search(&target);
+                // std may land on any of several equal elements, so only compare
+                // the found value, but insertion points have to agree exactly
+                match (ours, std) {
+                    (Ok(a), Ok(b)) => assert_eq!(test1[a], test1[b]),
+                    (a, b) => assert_eq!(a, b),
+                }
+                assert_eq!(
+                    partition_point(&test1, |x| *x < target),
+                    test1.partition_point(|x| *x < target)
+                );
+                assert_eq!(
+                    equal_range(&test1, &target).len(),
+                    test1.iter().filter(|x| **x == target).count()
+                );
+            }
+        }
+    }
+
+    fn strategies() -> Vec<Box<dyn SearchStrategy<i32>>> {
+        vec![
+            Box::new(BinarySearch),
+            Box::new(ExponentialSearch),
+            Box::new(InterpolationSearch),
+            Box::new(TernarySearch),
+            Box::new(JumpSearch),
+            Box::new(FibonacciSearch),
+        ]
+    }
+
+    #[test]
+    fn test_strategies() {
+        let test1 = vec![1, 3, 5, 7, 9, 12, 22, 500, 7000];
+
+        for strategy in strategies() {
+            let name = strategy.name();
+            assert_eq!(strategy.search(&test1, &5), Some(2), "{}", name);
+            assert_eq!(strategy.search(&test1, &1), Some(0), "{}", name);
+            assert_eq!(strategy.search(&test1, &7000), Some(8), "{}", name);
+            assert_eq!(strategy.search(&test1, &17), None, "{}", name);
+            assert_eq!(strategy.search(&test1, &0), None, "{}", name);
+            assert_eq!(strategy.search(&test1, &9000), None, "{}", name);
+            assert_eq!(strategy.search(&[], &1), None, "{}", name);
+            assert_eq!(strategy.search(&[4], &4), Some(0), "{}", name);
+            assert_eq!(strategy.search(&[4], &5), None, "{}", name);
+        }
+
+        // extreme values shouldn't overflow the interpolation math
+        let wide = [i64::MIN, -1, 0, 1, i64::MAX];
+        for (idx, val) in wide.iter().enumerate() {
+            assert_eq!(InterpolationSearch.search(&wide, val), Some(idx));
+        }
+        assert_eq!(InterpolationSearch.search(&wide, &2), None);
+    }
+
+    #[test]
+    fn test_strategies_match_binary_search() {
+        for count in 0..60 {
+            let mut test1 = gen_random_vector(-100, 100, count);
+            test1.sort_unstable();
+            let right_pointer = test1.len().saturating_sub(1) as u32;
+
+            for target in -105..105 {
+                let expected = binary_search(test1.clone(), 0, right_pointer, target);
+
+                for strategy in strategies() {
+                    let found = strategy.search(&test1, &target);
+                    // with duplicates the strategies may pick different copies, so
+                    // compare what was found rather than where
+                    assert_eq!(
+                        found.map(|idx| test1[idx]),
+                        expected.map(|idx| test1[idx]),
+                        "{} searching for {} in {:?}",
+                        strategy.name(),
+                        target,
+                        test1
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_first_true() {
+        assert_eq!(first_true(0..100, |x: i32| x * x >= 50), Some(8));
+        assert_eq!(first_true(0..100, |_: i32| true), Some(0));
+        assert_eq!(first_true(0..100, |_: i32| false), None);
+        assert_eq!(first_true(5..5, |_: i32| true), None);
+        let (start, end) = (7, 3);
+        assert_eq!(first_true(start..end, |_: i32| true), None);
+
+        // the full width of every type, with answers right at the edges
+        assert_eq!(first_true(i8::MIN..i8::MAX, |x| x >= -3), Some(-3));
+        assert_eq!(first_true(i8::MIN..i8::MAX, |_| true), Some(i8::MIN));
+        assert_eq!(
+            first_true(i8::MIN..i8::MAX, |x| x >= i8::MAX - 1),
+            Some(i8::MAX - 1)
+        );
+        assert_eq!(first_true(u8::MIN..u8::MAX, |x| x > 200), Some(201));
+        assert_eq!(first_true(i64::MIN..i64::MAX, |x| x > 0), Some(1));
+        assert_eq!(
+            first_true(u64::MIN..u64::MAX, |x| x >= u64::MAX / 3),
+            Some(u64::MAX / 3)
+        );
+        assert_eq!(
+            first_true(i128::MIN..i128::MAX, |x| x > i128::MIN),
+            Some(i128::MIN + 1)
+        );
+        assert_eq!(
+            first_true(u128::MIN..u128::MAX, |x| x >= u128::MAX - 1),
+            Some(u128::MAX - 1)
+        );
+        assert_eq!(first_true(0..usize::MAX, |_| false), None);
+
+        // smallest integer whose square is at least n, for every n checked by brute force
+        for n in 0..500u32 {
+            let expected = (0..=n).find(|x| x * x >= n);
+            assert_eq!(first_true(0..n + 1, |x| x * x >= n), expected);
+        }
+    }
+
+    #[test]
+    fn test_bisect() {
+        let tolerance = Tolerance::default();
+
+        let root = bisect(0.0, 2.0, |x| x * x >= 2.0, tolerance).unwrap();
+        assert!((root - 2f64.sqrt()).abs() <= 1e-9);
+        assert!(root * root >= 2.0);
+
+        // relative tolerance lets big answers stop early instead of chasing ulps
+        let loose = Tolerance {
+            abs: 0.0,
+            rel: 1e-6,
+            max_iterations: 200,
+        };
+        let big = bisect(0.0, 1e12, |x| x >= 123_456_789.0, loose).unwrap();
+        assert!((big - 123_456_789.0).abs() <= 1e-6 * 1e12);
+
+        // zero tolerance still stops once the ends are neighbouring floats
+        let exact = Tolerance {
+            abs: 0.0,
+            rel: 0.0,
+            max_iterations: 2000,
+        };
+        assert_eq!(bisect(0.0, 10.0, |x| x >= 3.5, exact), Ok(3.5));
+        assert!(bisect(f64::MIN, f64::MAX, |x| x >= -1e300, exact).is_ok());
+
+        assert_eq!(bisect(1.0, 2.0, |_| true, tolerance), Ok(1.0));
+        assert_eq!(
+            bisect(1.0, 2.0, |_| false, tolerance),
+            Err(BisectError::NeverTrue)
+        );
+        assert_eq!(
+            bisect(2.0, 1.0, |_| true, tolerance),
+            Err(BisectError::InvalidRange)
+        );
+        assert_eq!(
+            bisect(f64::NAN, 1.0, |_| true, tolerance),
+            Err(BisectError::InvalidRange)
+        );
+        assert_eq!(
+            bisect(f64::NEG_INFINITY, 0.0, |x| x >= -1.0, tolerance),
+            Err(BisectError::InvalidRange)
+        );
+        assert_eq!(
+            bisect(0.0, f64::INFINITY, |x| x >= 1.0, exact),
+            Err(BisectError::InvalidRange)
+        );
+        assert_eq!(
+            bisect(f64::NEG_INFINITY, f64::INFINITY, |_| true, tolerance),
+            Err(BisectError::InvalidRange)
+        );
+
+        let capped = Tolerance {
+            abs: 1e-12,
+            rel: 0.0,
+            max_iterations: 5,
+        };
+        match bisect(0.0, 1.0, |x| x >= 0.3, capped) {
+            Err(BisectError::NotConverged { low, high }) => {
+                assert!(low < 0.3 && high >= 0.3);
+                assert!(high - low <= 1.0 / 32.0);
+            }
+            other => panic!("expected non-convergence, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_search_rotated() {
+        let sorted = vec![1, 3, 5, 7, 9, 12, 22, 500, 7000];
+
+        for rotations in 0..sorted.len() as u16 {
+            let rotated = rotate_array(sorted.clone(), rotations);
+            let pivot = rotations as usize % sorted.len();
+            assert_eq!(find_rotation_point(&rotated), pivot, "{:?}", rotated);
+            assert_eq!(find_rotation_point_dups(&rotated), pivot, "{:?}", rotated);
+
+            for target in -1..7002 {
+                let expected = rotated.iter().position(|&val| val == target);
+                assert_eq!(search_rotated(&rotated, &target), expected);
+                assert_eq!(search_rotated_dups(&rotated, &target), expected);
+            }
+        }
+
+        assert_eq!(find_rotation_point::<i32>(&[]), 0);
+        assert_eq!(search_rotated(&[], &1), None);
+        assert_eq!(search_rotated_dups(&[], &1), None);
+        assert_eq!(find_rotation_point_dups(&[1, 1, 1, 0, 1, 1, 1, 1]), 3);
+        assert_eq!(find_rotation_point_dups(&[1, 1, 1, 1, 1, 0, 1]), 5);
+        assert_eq!(find_rotation_point_dups(&[2, 2, 2]), 0);
+    }
+
+    #[test]
+    fn test_search_rotated_dups_random() {
+        for count in 1..40 {
+            let mut sorted = gen_random_vector(0, 8, count);
+            sorted.sort_unstable();
+
+            for rotations in 0..count as u16 {
+                let rotated = rotate_array(sorted.clone(), rotations);
+
+                // the rotation point might not be unique, but the slice has to be
+                // sorted starting from it
+                let pivot = find_rotation_point_dups(&rotated);
+                let unrotated = [&rotated[pivot..], &rotated[..pivot]].concat();
+                assert_eq!(unrotated, sorted, "{:?}", rotated);
+
+                for target in -1..9 {
+                    let found = search_rotated_dups(&rotated, &target);
+                    assert_eq!(
+                        found.map(|idx| rotated[idx]),
+                        sorted.iter().find(|&&val| val == target).copied()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_peak() {
+        assert_eq!(find_peak::<i32>(&[]), None);
+        assert_eq!(find_peak(&[4]), Some(0));
+        assert_eq!(find_peak(&[1, 2, 3]), Some(2));
+        assert_eq!(find_peak(&[3, 2, 1]), Some(0));
+        assert_eq!(find_peak(&[1, 3, 2]), Some(1));
+
+        for count in 1..50 {
+            let test1 = gen_random_vector(0, 5, count);
+            let peak = find_peak(&test1).unwrap();
+
+            assert!(peak == 0 || test1[peak - 1] <= test1[peak], "{:?}", test1);
+            assert!(
+                peak + 1 == test1.len() || test1[peak + 1] <= test1[peak],
+                "{:?}",
+                test1
+            );
+        }
+    }
+
+    #[test]
+    fn test_search_bitonic() {
+        let test1 = vec![1, 4, 9, 12, 40, 33, 20, 7, 2];
+        for target in 0..45 {
+            let expected = test1.iter().position(|&val| val == target);
+            assert_eq!(search_bitonic(&test1, &target), expected);
+            assert_eq!(search_bitonic_dups(&test1, &target), expected);
+        }
+        assert_eq!(search_bitonic(&[], &1), None);
+        assert_eq!(search_bitonic_dups(&[], &1), None);
+        assert_eq!(search_bitonic(&[1, 2, 3], &3), Some(2));
+        assert_eq!(search_bitonic(&[3, 2, 1], &3), Some(0));
+
+        // a flat run right before the top is what trips up the plain version
+        let flat = [1, 2, 2, 2, 2, 2, 9, 2];
+        assert_eq!(search_bitonic_dups(&flat, &9), Some(6));
+
+        for count in 1..40 {
+            let mut rising = gen_random_vector(0, 10, count);
+            let mut falling = gen_random_vector(0, 10, count / 2);
+            rising.sort_unstable();
+            falling.sort_unstable_by(|a, b| b.cmp(a));
+            let test2 = [rising, falling].concat();
+
+            for target in -1..11 {
+                let found = search_bitonic_dups(&test2, &target);
+                assert_eq!(
+                    found.map(|idx| test2[idx]),
+                    test2.iter().find(|&&val| val == target).copied(),
+                    "{:?}",
+                    test2
+                );
+            }
+        }
+    }
+
+    // checks the nth element is in place and the slice is partitioned around it
+    fn assert_selected(selected: &[i32], original: &[i32], n: usize) {
+        let mut sorted = original.to_vec();
+        sorted.sort_unstable();
+
+        assert_eq!(selected[n], sorted[n], "{:?}", original);
+        assert!(selected[..n].iter().all(|&val| val <= selected[n]));
+        assert!(selected[n..].iter().all(|&val| val >= selected[n]));
+
+        let mut rearranged = selected.to_vec();
+        rearranged.sort_unstable();
+        assert_eq!(rearranged, sorted);
+    }
+
+    #[test]
+    fn test_log2_ceil() {
+        let expected = [
+            (0, 0),
+            (1, 0),
+            (2, 1),
+            (3, 2),
+            (4, 2),
+            (5, 3),
+            (8, 3),
+            (9, 4),
+        ];
+        for (n, log) in expected {
+            assert_eq!(log2_ceil(n), log, "{}", n);
+        }
+        assert_eq!(log2_ceil(usize::MAX), usize::BITS as usize);
+        assert_eq!(log2_ceil(1 << 40), 40);
+    }
+
+    #[test]
+    fn test_select_nth() {
+        assert_eq!(select_nth::<i32>(&mut [], 0), None);
+        assert_eq!(select_nth(&mut [3, 1, 2], 3), None);
+        assert_eq!(select_nth(&mut [3, 1, 2], 0), Some(&mut 1));
+        assert_eq!(select_nth(&mut [3, 1, 2], 2), Some(&mut 3));
+
+        for count in 1..200 {
+            let original = gen_random_vector(-50, 50, count);
+            let len = original.len();
+            for n in (0..len).step_by(7).chain(Some(len - 1)) {
+                let mut test1 = original.clone();
+                select_nth(&mut test1, n);
+                assert_selected(&test1, &original, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_nth_adversarial() {
+        let n = 1000;
+        let inputs: Vec<Vec<i32>> = vec![
+            (0..n).collect(),
+            (0..n).rev().collect(),
+            vec![7; n as usize],
+            // organ pipe
+            (0..n / 2).chain((0..n / 2).rev()).collect(),
+            (0..n).map(|val| val % 3).collect(),
+        ];
+
+        for original in inputs {
+            for &k in &[0, 1, 250, 499, 500, 998, 999] {
+                let mut test1 = original.clone();
+                select_nth(&mut test1, k);
+                assert_selected(&test1, &original, k);
+
+                // no pivot budget, so median-of-medians all the way down
+                let mut test2 = original.clone();
+                select_in(&mut test2, k, &mut i32::cmp, 0);
+                assert_selected(&test2, &original, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_median_and_top_k() {
+        assert_eq!(median::<i32>(&mut []), None);
+        assert_eq!(median(&mut [5]), Some(&mut 5));
+        assert_eq!(median(&mut [9, 1, 5]), Some(&mut 5));
+        // lower middle for an even length
+        assert_eq!(median(&mut [9, 1, 5, 7]), Some(&mut 5));
+
+        let mut words = vec!["pear", "fig", "banana", "kiwi", "apple"];
+        assert_eq!(
+            median_by(&mut words, |a, b| a.len().cmp(&b.len())).map(|word| word.len()),
+            Some(4)
+        );
+
+        let original = gen_random_vector(0, 1000, 500);
+        let mut sorted = original.clone();
+        sorted.sort_unstable();
+
+        for &k in &[0, 1, 10, 499, 500, 600] {
+            let mut test1 = original.clone();
+            let smallest = k_smallest(&mut test1, k);
+            assert_eq!(smallest, &sorted[..k.min(500)]);
+
+            let mut test2 = original.clone();
+            let largest = k_largest(&mut test2, k);
+            let expected: Vec<i32> = sorted.iter().rev().take(k).copied().collect();
+            assert_eq!(largest, &expected[..]);
+        }
+
+        // comparator versions, ordering by distance from 500
+        let mut test3 = original.clone();
+        let closest = k_smallest_by(&mut test3, 5, |a, b| (a - 500).abs().cmp(&(b - 500).abs()));
+        let mut by_distance: Vec<i32> = original.iter().map(|val| (val - 500).abs()).collect();
+        by_distance.sort_unstable();
+        let distances: Vec<i32> = closest.iter().map(|val| (val - 500).abs()).collect();
+        assert_eq!(distances, &by_distance[..5]);
+
+        let mut test4 = original;
+        let farthest = k_largest_by(&mut test4, 3, |a, b| (a - 500).abs().cmp(&(b - 500).abs()));
+        let distances: Vec<i32> = farthest.iter().map(|val| (val - 500).abs()).collect();
+        let expected: Vec<i32> = by_distance.iter().rev().take(3).copied().collect();
+        assert_eq!(distances, expected);
+    }
+
+    // every cell of the matrix equal to target
+    fn cells_equal<M: Matrix<i32> + ?Sized>(matrix: &M, target: i32) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+        for row in 0..matrix.rows() {
+            for col in 0..matrix.cols() {
+                if matrix.get(row, col) == Some(&target) {
+                    cells.push((row, col));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_search_sorted_matrix() {
+        let grid = vec![
+            vec![1, 4, 7, 11, 15],
+            vec![2, 5, 8, 12, 19],
+            vec![3, 6, 9, 16, 22],
+            vec![10, 13, 14, 17, 24],
+            vec![18, 21, 23, 26, 30],
+        ];
+        let flat = grid.concat();
+        let flat = FlatMatrix::new(&flat, 5);
+
+        assert_eq!(search_sorted_matrix(&grid, &5), Some((1, 1)));
+        assert_eq!(search_sorted_matrix(&grid[..], &18), Some((4, 0)));
+        assert_eq!(search_sorted_matrix(&grid, &15), Some((0, 4)));
+        assert_eq!(search_sorted_matrix(&flat, &30), Some((4, 4)));
+        assert_eq!(search_sorted_matrix(&flat, &20), None);
+        assert_eq!(search_sorted_matrix(&grid, &0), None);
+        assert_eq!(search_sorted_matrix(&grid, &31), None);
+
+        let empty: Vec<Vec<i32>> = Vec::new();
+        assert_eq!(search_sorted_matrix(&empty, &1), None);
+        assert_eq!(search_sorted_matrix(&vec![Vec::<i32>::new()], &1), None);
+        assert_eq!(search_sorted_matrix(&FlatMatrix::new(&[1, 2], 0), &1), None);
+
+        // random matrices where each cell is at least the ones above and to its left
+        for size in 1..12u32 {
+            let steps = gen_random_vector(0, 3, size * size);
+            let width = size as usize;
+            let mut grid = vec![vec![0; width]; width];
+            for row in 0..width {
+                for col in 0..width {
+                    let above = if row > 0 { grid[row - 1][col] } else { 0 };
+                    let left = if col > 0 { grid[row][col - 1] } else { 0 };
+                    grid[row][col] = above.max(left) + steps[row * width + col];
+                }
+            }
+            let buffer = grid.concat();
+            let flat = FlatMatrix::new(&buffer, width);
+
+            for target in -1..(grid[width - 1][width - 1] + 2) {
+                let expected = cells_equal(&grid, target);
+                let found = search_sorted_matrix(&grid, &target);
+                assert_eq!(
+                    found.is_some(),
+                    !expected.is_empty(),
+                    "{} in {:?}",
+                    target,
+                    grid
+                );
+                assert!(found.is_none_or(|cell| expected.contains(&cell)));
+                assert_eq!(search_sorted_matrix(&flat, &target), found);
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_row_major_sorted() {
+        let grid = vec![vec![1, 3, 5, 7], vec![10, 11, 16, 20], vec![23, 30, 34, 60]];
+        let buffer = grid.concat();
+        let flat = FlatMatrix::new(&buffer, 4);
+
+        for (idx, val) in buffer.iter().enumerate() {
+            let expected = Some((idx / 4, idx % 4));
+            assert_eq!(search_row_major_sorted(&grid, val), expected);
+            assert_eq!(search_row_major_sorted(&flat, val), expected);
+        }
+        for target in &[0, 2, 13, 61] {
+            assert_eq!(search_row_major_sorted(&grid, target), None);
+            assert_eq!(search_row_major_sorted(&flat, target), None);
+        }
+
+        let empty: Vec<Vec<i32>> = Vec::new();
+        assert_eq!(search_row_major_sorted(&empty, &1), None);
+        assert_eq!(
+            search_row_major_sorted(&FlatMatrix::new(&[1, 2], 0), &1),
+            None
+        );
+
+        // the leftover 7 doesn't make a full row
+        let partial = FlatMatrix::new(&[1, 2, 3, 4, 5, 6, 7], 3);
+        assert_eq!(partial.rows(), 2);
+        assert_eq!(search_row_major_sorted(&partial, &6), Some((1, 2)));
+        assert_eq!(search_row_major_sorted(&partial, &7), None);
+
+        // ragged rows don't panic
+        let ragged = vec![vec![1, 2, 3], vec![4], vec![5, 6, 7]];
+        search_row_major_sorted(&ragged, &6);
+        search_sorted_matrix(&ragged, &6);
+
+        for count in 1..40u32 {
+            let mut values = gen_random_vector(0, 30, count);
+            values.sort_unstable();
+            for width in 1..6 {
+                let rows = values.len() / width;
+                let grid: Vec<Vec<i32>> = values[..rows * width]
+                    .chunks(width)
+                    .map(|row| row.to_vec())
+                    .collect();
+
+                for target in -1..31 {
+                    let expected = cells_equal(&grid, target);
+                    // ties report the first cell in row-major order
+                    assert_eq!(
+                        search_row_major_sorted(&grid, &target),
+                        expected.first().copied()
+                    );
+                }
+            }
+        }
+    }
 }
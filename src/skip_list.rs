@@ -0,0 +1,712 @@
+use std::borrow::Borrow;
+use std::fmt::{self, Debug};
+use std::iter::FromIterator;
+use std::ops::{Bound, RangeBounds};
+
+use rand::rngs::StdRng;
+
+use crate::math;
+
+// Ordered map built from stacked linked lists. Every entry is a Node holding a
+// tower of forward links: level 0 links every entry in key order, and each level
+// above skips over more entries, so a search can drop down the towers in
+// O(log n) expected steps. Tower heights are random (see math::geometric_level).
+//
+// Nodes live in a Vec and link to each other by index, like arena_list::ArenaList.
+// Each link also records its width, the number of level 0 steps it jumps over,
+// which is what makes rank / select (index_of / nth) O(log n) too.
+
+const MAX_LEVEL: usize = 32;
+// chance of a tower growing one more level
+const P: f64 = 0.5;
+
+// Stands in for a null link
+const NIL: usize = usize::MAX;
+// Refers to the head tower, which has no key and isn't stored in the slots
+const HEAD: usize = usize::MAX - 1;
+
+#[derive(Clone, Copy)]
+struct Link {
+    next: usize,
+    width: usize,
+}
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    forward: Vec<Link>,
+}
+
+pub struct SkipList<K, V> {
+    head: Vec<Link>,
+    slots: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    len: usize,
+    rng: StdRng,
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    // Tower heights come from an entropy seeded generator
+    pub fn new() -> Self {
+        Self::with_rng(math::entropy_rng())
+    }
+
+    // The same seed and the same operations always build the same towers
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(math::seeded_rng(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
+        SkipList {
+            head: Vec::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            rng,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.head.clear();
+        self.slots.clear();
+        self.free.clear();
+        self.len = 0;
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.find(key)?;
+        Some(&self.node(idx).value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.find(key)?;
+        Some(&mut self.node_mut(idx).value)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    // Inserts a key, or replaces the value of an existing key and returns the old one
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(existing) = self.find(&key) {
+            return Some(std::mem::replace(&mut self.node_mut(existing).value, value));
+        }
+
+        let (mut update, mut rank) = self.search_path(&key);
+
+        let height = math::geometric_level(&mut self.rng, P, MAX_LEVEL);
+        // new levels start out as a single head link that spans the whole list
+        while self.head.len() < height {
+            self.head.push(Link {
+                next: NIL,
+                width: self.len + 1,
+            });
+            update.push(HEAD);
+            rank.push(0);
+        }
+
+        // position of the new node counting the head as position 0
+        let position = rank[0] + 1;
+        let mut forward = Vec::with_capacity(height);
+        for (level, (&prev, &prev_rank)) in update.iter().zip(&rank).enumerate().take(height) {
+            // split prev's link in two around the new node
+            let old = self.link(prev, level);
+            forward.push(Link {
+                next: old.next,
+                width: old.width + 1 - (position - prev_rank),
+            });
+            self.link_mut(prev, level).width = position - prev_rank;
+        }
+
+        let idx = self.alloc(Node {
+            key,
+            value,
+            forward,
+        });
+        for (level, &prev) in update.iter().enumerate().take(height) {
+            self.link_mut(prev, level).next = idx;
+        }
+
+        // taller links now jump over one more node
+        for (level, &prev) in update.iter().enumerate().skip(height) {
+            self.link_mut(prev, level).width += 1;
+        }
+
+        self.len += 1;
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.find(key)?;
+        let (update, _) = self.search_path(key);
+
+        for (level, &prev) in update.iter().enumerate() {
+            let prev_link = self.link(prev, level);
+            if prev_link.next == idx {
+                // bridge over the removed node, merging the two widths
+                let removed = self.node(idx).forward[level];
+                *self.link_mut(prev, level) = Link {
+                    next: removed.next,
+                    width: prev_link.width + removed.width - 1,
+                };
+            } else {
+                self.link_mut(prev, level).width -= 1;
+            }
+        }
+
+        // drop levels that no longer hold any node
+        while self.head.last().is_some_and(|link| link.next == NIL) {
+            self.head.pop();
+        }
+
+        self.len -= 1;
+        let node = self.slots[idx].take().expect("link points at an empty slot");
+        self.free.push(idx);
+        Some(node.value)
+    }
+
+    // Select: the entry at position idx in key order
+    pub fn nth(&self, idx: usize) -> Option<(&K, &V)> {
+        if idx >= self.len {
+            return None;
+        }
+
+        // walk down the towers, taking any link that doesn't overshoot the target
+        let target = idx + 1;
+        let mut position = 0;
+        let mut node = HEAD;
+        for level in (0..self.head.len()).rev() {
+            loop {
+                let link = self.link(node, level);
+                if link.next == NIL || position + link.width > target {
+                    break;
+                }
+                position += link.width;
+                node = link.next;
+            }
+        }
+
+        let node = self.node(node);
+        Some((&node.key, &node.value))
+    }
+
+    // Rank: the position of key in key order
+    pub fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key)?;
+        Some(self.count_below(key, false).0)
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.nth(0)
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.len.checked_sub(1).and_then(|idx| self.nth(idx))
+    }
+
+    // Iterates over every entry in key order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            slots: &self.slots,
+            next: self.head.first().map_or(NIL, |link| link.next),
+            remaining: self.len,
+        }
+    }
+
+    // Iterates over the entries whose keys fall inside range, in key order
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        // count the entries below the start and the end of the range, which gives
+        // both the first node in range and how many nodes to yield from there
+        let (start_count, before_start) = match range.start_bound() {
+            Bound::Included(key) => self.count_below(key, false),
+            Bound::Excluded(key) => self.count_below(key, true),
+            Bound::Unbounded => (0, HEAD),
+        };
+        let end_count = match range.end_bound() {
+            Bound::Included(key) => self.count_below(key, true).0,
+            Bound::Excluded(key) => self.count_below(key, false).0,
+            Bound::Unbounded => self.len,
+        };
+
+        let next = if self.head.is_empty() {
+            NIL
+        } else {
+            self.link(before_start, 0).next
+        };
+
+        Iter {
+            slots: &self.slots,
+            next,
+            remaining: end_count.saturating_sub(start_count),
+        }
+    }
+
+    // Number of keys below key (or at most key when inclusive), plus the last node
+    // passed on the way, which is the node the next key in order hangs off of
+    fn count_below<Q>(&self, key: &Q, inclusive: bool) -> (usize, usize)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut count = 0;
+        let mut node = HEAD;
+        for level in (0..self.head.len()).rev() {
+            loop {
+                let link = self.link(node, level);
+                if link.next == NIL {
+                    break;
+                }
+                let next_key = self.node(link.next).key.borrow();
+                if next_key < key || (inclusive && next_key == key) {
+                    count += link.width;
+                    node = link.next;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        (count, node)
+    }
+
+    // For each level, the last node with a key below key and that node's position
+    fn search_path<Q>(&self, key: &Q) -> (Vec<usize>, Vec<usize>)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let height = self.head.len();
+        let mut update = vec![HEAD; height];
+        let mut rank = vec![0; height];
+
+        let mut position = 0;
+        let mut node = HEAD;
+        for level in (0..height).rev() {
+            loop {
+                let link = self.link(node, level);
+                if link.next == NIL || self.node(link.next).key.borrow() >= key {
+                    break;
+                }
+                position += link.width;
+                node = link.next;
+            }
+            update[level] = node;
+            rank[level] = position;
+        }
+
+        (update, rank)
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if self.head.is_empty() {
+            return None;
+        }
+
+        let (_, last) = self.count_below(key, false);
+        let next = self.link(last, 0).next;
+        if next != NIL && self.node(next).key.borrow() == key {
+            Some(next)
+        } else {
+            None
+        }
+    }
+
+    fn alloc(&mut self, node: Node<K, V>) -> usize {
+        match self.free.pop() {
+            Some(idx) => {
+                self.slots[idx] = Some(node);
+                idx
+            }
+            None => {
+                self.slots.push(Some(node));
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    fn link(&self, node: usize, level: usize) -> Link {
+        if node == HEAD {
+            self.head[level]
+        } else {
+            self.node(node).forward[level]
+        }
+    }
+
+    fn link_mut(&mut self, node: usize, level: usize) -> &mut Link {
+        if node == HEAD {
+            &mut self.head[level]
+        } else {
+            &mut self.node_mut(node).forward[level]
+        }
+    }
+
+    fn node(&self, idx: usize) -> &Node<K, V> {
+        self.slots[idx].as_ref().expect("link points at an empty slot")
+    }
+
+    fn node_mut(&mut self, idx: usize) -> &mut Node<K, V> {
+        self.slots[idx].as_mut().expect("link points at an empty slot")
+    }
+}
+
+impl<K: Ord, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for SkipList<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut list = SkipList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for SkipList<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for SkipList<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = Iter {
+            slots: &self.slots,
+            next: self.head.first().map_or(NIL, |link| link.next),
+            remaining: self.len,
+        };
+        f.debug_map().entries(entries).finish()
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    slots: &'a [Option<Node<K, V>>],
+    next: usize,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let node = self.slots.get(self.next)?.as_ref()?;
+        self.next = node.forward[0].next;
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<'a, K: Ord, V> IntoIterator for &'a SkipList<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+// Ordered set on top of SkipList, storing () as every value
+pub struct SkipSet<T> {
+    map: SkipList<T, ()>,
+}
+
+impl<T: Ord> SkipSet<T> {
+    pub fn new() -> Self {
+        SkipSet {
+            map: SkipList::new(),
+        }
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        SkipSet {
+            map: SkipList::with_seed(seed),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    // Returns false if the value was already in the set
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    pub fn nth(&self, idx: usize) -> Option<&T> {
+        self.map.nth(idx).map(|(key, _)| key)
+    }
+
+    pub fn index_of<Q>(&self, value: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.index_of(value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.map.iter().map(|(key, _)| key)
+    }
+
+    pub fn range<Q, R>(&self, range: R) -> impl Iterator<Item = &T> + '_
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.map.range(range).map(|(key, _)| key)
+    }
+}
+
+impl<T: Ord> Default for SkipSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SkipSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = SkipSet::new();
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+impl<T: Debug> Debug for SkipSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keys = Iter {
+            slots: &self.map.slots,
+            next: self.map.head.first().map_or(NIL, |link| link.next),
+            remaining: self.map.len,
+        }
+        .map(|(key, _)| key);
+        f.debug_set().entries(keys).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    // Walks every level and checks that links are sorted and their widths add up
+    fn check_invariants(list: &SkipList<i32, i32>) {
+        for level in 0..list.head.len() {
+            let mut position = 0;
+            let mut node = HEAD;
+            let mut prev_key = None;
+            loop {
+                let link = list.link(node, level);
+                position += link.width;
+                if link.next == NIL {
+                    assert_eq!(position, list.len + 1, "widths on level {}", level);
+                    break;
+                }
+                let key = list.node(link.next).key;
+                assert!(prev_key.is_none_or(|prev| prev < key));
+                assert_eq!(list.index_of(&key), Some(position - 1));
+                prev_key = Some(key);
+                node = link.next;
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut list = SkipList::with_seed(7);
+        assert!(list.is_empty());
+        assert_eq!(list.get(&1), None);
+        assert_eq!(list.remove(&1), None);
+        assert_eq!(list.index_of(&1), None);
+        assert_eq!(list.nth(0), None);
+
+        assert_eq!(list.insert(5, 50), None);
+        assert_eq!(list.insert(1, 10), None);
+        assert_eq!(list.insert(3, 30), None);
+        assert_eq!(list.insert(3, 33), Some(30));
+        assert_eq!(list.len(), 3);
+
+        assert_eq!(list.get(&3), Some(&33));
+        assert!(list.contains_key(&5));
+        if let Some(val) = list.get_mut(&1) {
+            *val += 1;
+        }
+        assert_eq!(list.get(&1), Some(&11));
+
+        assert_eq!(list.remove(&3), Some(33));
+        assert_eq!(list.remove(&3), None);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![1, 5]);
+        check_invariants(&list);
+    }
+
+    #[test]
+    fn test_rank_and_select() {
+        let list: SkipList<i32, i32> = [40, 10, 30, 20, 50].iter().map(|&k| (k, k * 2)).collect();
+
+        assert_eq!(list.nth(0), Some((&10, &20)));
+        assert_eq!(list.nth(3), Some((&40, &80)));
+        assert_eq!(list.nth(5), None);
+        assert_eq!(list.first(), Some((&10, &20)));
+        assert_eq!(list.last(), Some((&50, &100)));
+
+        assert_eq!(list.index_of(&10), Some(0));
+        assert_eq!(list.index_of(&50), Some(4));
+        assert_eq!(list.index_of(&35), None);
+    }
+
+    #[test]
+    fn test_range() {
+        let list: SkipList<i32, ()> = (0..20).map(|k| (k * 5, ())).collect();
+        let keys = |iter: Iter<i32, ()>| iter.map(|(k, _)| *k).collect::<Vec<_>>();
+
+        assert_eq!(keys(list.range(10..25)), vec![10, 15, 20]);
+        assert_eq!(keys(list.range(11..=25)), vec![15, 20, 25]);
+        assert_eq!(keys(list.range(..10)), vec![0, 5]);
+        assert_eq!(keys(list.range(90..)), vec![90, 95]);
+        assert_eq!(keys(list.range((Bound::Excluded(90), Bound::Unbounded))), vec![95]);
+        assert_eq!(keys(list.range(40..40)), Vec::<i32>::new());
+        assert_eq!(keys(list.range(200..300)), Vec::<i32>::new());
+        assert_eq!(list.range(..).len(), 20);
+
+        let empty: SkipList<i32, ()> = SkipList::new();
+        assert_eq!(empty.range(1..5).next(), None);
+    }
+
+    #[test]
+    fn test_seeded_lists_match() {
+        let mut a = SkipList::with_seed(99);
+        let mut b = SkipList::with_seed(99);
+        for k in 0..200 {
+            a.insert(k, ());
+            b.insert(k, ());
+        }
+
+        // same seed, same towers
+        assert_eq!(a.head.len(), b.head.len());
+        let heights = |list: &SkipList<i32, ()>| {
+            list.slots
+                .iter()
+                .map(|slot| slot.as_ref().unwrap().forward.len())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(heights(&a), heights(&b));
+    }
+
+    #[test]
+    fn test_matches_btreemap() {
+        let mut list = SkipList::with_seed(1);
+        let mut model = BTreeMap::new();
+        let mut rng = math::seeded_rng(2);
+
+        for step in 0..2_000 {
+            let key = math::geometric_level(&mut rng, 0.97, 200) as i32;
+            if step % 3 == 0 {
+                assert_eq!(list.remove(&key), model.remove(&key));
+            } else {
+                assert_eq!(list.insert(key, step), model.insert(key, step));
+            }
+        }
+
+        assert_eq!(list.len(), model.len());
+        assert!(list.iter().eq(model.iter()));
+        for (i, (k, v)) in model.iter().enumerate() {
+            assert_eq!(list.nth(i), Some((k, v)));
+        }
+        assert!(list.range(20..60).eq(model.range(20..60)));
+        check_invariants(&list);
+    }
+
+    #[test]
+    fn test_skip_set() {
+        let mut set = SkipSet::with_seed(3);
+        assert!(set.insert("pear"));
+        assert!(set.insert("apple"));
+        assert!(!set.insert("pear"));
+        assert!(set.insert("fig"));
+
+        assert_eq!(set.len(), 3);
+        assert!(set.contains("fig"));
+        assert_eq!(set.nth(1), Some(&"fig"));
+        assert_eq!(set.index_of("pear"), Some(2));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec!["apple", "fig", "pear"]);
+        assert_eq!(set.range("b".."g").copied().collect::<Vec<_>>(), vec!["fig"]);
+        assert_eq!(format!("{:?}", set), r#"{"apple", "fig", "pear"}"#);
+
+        assert!(set.remove("apple"));
+        assert!(!set.remove("apple"));
+        assert_eq!(set.nth(0), Some(&"fig"));
+    }
+}
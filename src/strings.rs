@@ -1,52 +1,23 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+use std::mem;
+use std::ops::Range;
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::search::partition_point;
 
 // Given a string containing just the characters '(', ')', '{', '}', '[' and ']'
 // determine if the input string is valid. Open brackets must be closed by
 // the same type of brackets && Open brackets must be closed in the correct order.
+// Any other characters are ignored. DelimiterChecker does the work, and says
+// where things went wrong when they do
 pub fn bracket_checker(input: String) -> bool {
-    // base case returns true if input is an empty String
-    if input == String::from("") {
-        return true;
-    }
-
-    let mut brackets: Vec<char> = Vec::with_capacity(input.len());
-
-    for c in input.chars() {
-        match c {
-            '[' => brackets.push(c),
-            '(' => brackets.push(c),
-            '{' => brackets.push(c),
-            _ => {
-                match brackets.pop().unwrap() {
-                    '[' => {
-                        if c == ']' {
-                            continue;
-                        } else {
-                            return false;
-                        }
-                    }
-                    '{' => {
-                        if c == '}' {
-                            continue;
-                        } else {
-                            return false;
-                        }
-                    }
-                    '(' => {
-                        if c == ')' {
-                            continue;
-                        } else {
-                            return false;
-                        }
-                    }
-                    _ => return false, // return false if the character isn't allowed
-                }
-            }
-        }
-    }
-
-    // evaluate to true if all brackets are properly closed
-    true
+    DelimiterChecker::new().check(&input).is_ok()
 }
 
 // Reverses a string in place
@@ -54,31 +25,61 @@ pub fn reverse_string(input: String) -> String {
     input.chars().rev().collect::<String>()
 }
 
-// Reverses the digits of an integer
-pub fn reverse_integer(input: i32) -> i32 {
-    match input < 0 {
-        // if the input is negative, multiply it by -1 before collecting
-        // the reversed digit chars into a String that can then be parsed back
-        // into a signed 32 bit integer. Multiply that by -1 to get the result
-        true => {
-            let temp = (input * -1)
-                .to_string()
-                .chars()
-                .rev()
-                .collect::<String>()
-                .parse::<i32>()
-                .unwrap();
-            temp * -1
-        }
-        // otherwise, collect the reversed input chars into a String and parse back into i32
-        false => input
-            .to_string()
-            .chars()
-            .rev()
-            .collect::<String>()
-            .parse::<i32>()
-            .unwrap(),
+// Reverses the base 10 digits of an integer, keeping its sign, so 120 becomes 21
+// and -30001 becomes -10003. Reversals that don't fit in the type saturate to its
+// MIN or MAX. See checked_reverse_digits and friends for other radixes and other
+// ways of handling overflow
+pub fn reverse_integer<T: RadixInt>(input: T) -> T {
+    saturating_reverse_digits(input, 10)
+}
+
+// Reverses the digits of input in the given radix, or None if the result doesn't
+// fit in T. This is all arithmetic: digits come off the bottom with % and /, and
+// get pushed onto the result with * and +. Since the remainder of a negative
+// number is negative, the result builds up on the same side of zero as the input,
+// which means even T::MIN never needs negating.
+//
+// Panics if radix isn't between 2 and 36
+pub fn checked_reverse_digits<T: RadixInt>(input: T, radix: u32) -> Option<T> {
+    reverse_digits_with(input, radix, |acc, radix, digit| {
+        acc.checked_mul(radix)?.checked_add(digit)
+    })
+}
+
+// Like checked_reverse_digits, but clamps to T::MIN or T::MAX on overflow
+pub fn saturating_reverse_digits<T: RadixInt>(input: T, radix: u32) -> T {
+    checked_reverse_digits(input, radix).unwrap_or(if input < T::ZERO { T::MIN } else { T::MAX })
+}
+
+// Like checked_reverse_digits, but wraps around on overflow, as if the digits had
+// been pushed with wrapping_mul and wrapping_add
+pub fn wrapping_reverse_digits<T: RadixInt>(input: T, radix: u32) -> T {
+    reverse_digits_with(input, radix, |acc, radix, digit| {
+        Some(acc.wrapping_mul(radix).wrapping_add(digit))
+    })
+    .unwrap()
+}
+
+// The shared loop of the reversals. push adds a digit to the end of the result so
+// far, or gives up with None
+fn reverse_digits_with<T, F>(mut input: T, radix: u32, mut push: F) -> Option<T>
+where
+    T: RadixInt,
+    F: FnMut(T, T, T) -> Option<T>,
+{
+    assert!(
+        (2..=36).contains(&radix),
+        "radix must be between 2 and 36, got {}",
+        radix
+    );
+
+    let radix = T::from_digit(radix);
+    let mut reversed = T::ZERO;
+    while input != T::ZERO {
+        reversed = push(reversed, radix, input.rem(radix))?;
+        input = input.div(radix);
     }
+    Some(reversed)
 }
 
 pub fn first_unique_character(input: String) -> i32 {
@@ -147,7 +148,7 @@ pub fn is_anagram(a: String, b: String) -> bool {
 
 // function that checks if the input string is a valid palindrome
 pub fn is_palindrome(input: String) -> bool {
-    if input == String::from("") {
+    if input.is_empty() {
         return true;
     };
     // first, clean the string so we only have lowercase alphanumeric characters
@@ -216,166 +217,3773 @@ pub fn longest_substring(input: String) -> Option<String> {
         sub_container.into_iter().collect::<String>(),
     );
 
-    match sub_dict.get(&longest) {
-        Some(val) => Some(val.to_string()),
-        None => None,
-    }
+    sub_dict.get(&longest).map(|val| val.to_string())
 }
 
+// Parses a base-10 integer the way C's atoi does: leading whitespace, an optional
+// sign, then digits up to the first character that isn't one, clamped to the i32
+// range. Anything without digits is 0
 pub fn string_to_int(s: String) -> i32 {
-    let mut negative = false;
-    let bound: i32 = 2147483647;
+    parse_int(&s, 10, ParseMode::Atoi).unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    // The whole string must be an optional sign followed by digits, with nothing
+    // else around them. Anything else is an error
+    Strict,
+    // Like C's atoi: skips leading whitespace, takes an optional sign and then as
+    // many digits as there are, ignoring the rest. Out of range values saturate to
+    // the type's MIN or MAX, and no digits at all gives 0. Never returns an error
+    Atoi,
+}
 
-    // check to see if the String slice starts with negative sign
-    if s.trim().starts_with("-") {
-        negative = true;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseIntError {
+    // there were no digits: an empty string, or just a sign
+    Empty,
+    // the character at byte offset pos isn't a digit in the radix (or is a '-' for
+    // an unsigned type)
+    InvalidDigit { pos: usize },
+    // the value doesn't fit in the type
+    Overflow,
+}
+
+impl fmt::Display for ParseIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseIntError::Empty => write!(f, "no digits to parse"),
+            ParseIntError::InvalidDigit { pos } => write!(f, "invalid digit at byte {}", pos),
+            ParseIntError::Overflow => write!(f, "number too large or too small for the type"),
+        }
     }
+}
 
-    // filter string so there are only base-10 digits
-    let clean = s.chars().filter(|c| c.is_digit(10)).collect::<String>();
+impl Error for ParseIntError {}
 
-    // clamp to i32 max / min
-    let parsed = match clean.parse::<i32>() {
-        Ok(val) => {
-            if negative {
-                val * -1
-            } else {
-                val
+// The integer types parse_int and the digit reversals work with. Digits and
+// radixes are at most 36, so they fit in every type, even i8
+pub trait RadixInt: Copy + PartialOrd {
+    const ZERO: Self;
+    const MIN: Self;
+    const MAX: Self;
+    const SIGNED: bool;
+
+    fn from_digit(digit: u32) -> Self;
+
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    fn wrapping_mul(self, rhs: Self) -> Self;
+
+    fn wrapping_add(self, rhs: Self) -> Self;
+
+    // Rust's / and %, which round toward zero, so a negative number's remainder is
+    // negative too
+    fn div(self, rhs: Self) -> Self;
+
+    fn rem(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_radix_int {
+    ($signed:expr; $($int:ty),*) => {
+        $(impl RadixInt for $int {
+            const ZERO: Self = 0;
+            const MIN: Self = <$int>::MIN;
+            const MAX: Self = <$int>::MAX;
+            const SIGNED: bool = $signed;
+
+            fn from_digit(digit: u32) -> Self {
+                digit as $int
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$int>::checked_mul(self, rhs)
+            }
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$int>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$int>::checked_sub(self, rhs)
+            }
+
+            fn wrapping_mul(self, rhs: Self) -> Self {
+                <$int>::wrapping_mul(self, rhs)
+            }
+
+            fn wrapping_add(self, rhs: Self) -> Self {
+                <$int>::wrapping_add(self, rhs)
+            }
+
+            fn div(self, rhs: Self) -> Self {
+                self / rhs
+            }
+
+            fn rem(self, rhs: Self) -> Self {
+                self % rhs
+            }
+        })*
+    };
+}
+
+impl_radix_int!(true; i8, i16, i32, i64, i128, isize);
+impl_radix_int!(false; u8, u16, u32, u64, u128, usize);
+
+// C's isspace, which unlike char::is_ascii_whitespace includes vertical tab
+fn is_c_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\x0B' | '\x0C' | '\r')
+}
+
+// Parses s as an integer of any width in the given radix. Letters are digits past
+// 9 in either case, as with char::to_digit. Negative numbers are built up by
+// subtracting each digit, so T::MIN parses without overflowing on the way.
+//
+// Panics if radix isn't between 2 and 36
+pub fn parse_int<T: RadixInt>(s: &str, radix: u32, mode: ParseMode) -> Result<T, ParseIntError> {
+    assert!(
+        (2..=36).contains(&radix),
+        "radix must be between 2 and 36, got {}",
+        radix
+    );
+
+    let start = match mode {
+        ParseMode::Strict => 0,
+        ParseMode::Atoi => s.len() - s.trim_start_matches(is_c_space).len(),
+    };
+    let mut chars = s[start..]
+        .char_indices()
+        .map(|(pos, c)| (pos + start, c))
+        .peekable();
+
+    let negative = match chars.peek() {
+        Some(&(_, '+')) => {
+            chars.next();
+            false
+        }
+        Some(&(pos, '-')) => {
+            if !T::SIGNED && mode == ParseMode::Strict {
+                return Err(ParseIntError::InvalidDigit { pos });
             }
+            chars.next();
+            true
         }
-        _ => {
+        _ => false,
+    };
+
+    let radix_value = T::from_digit(radix);
+    let mut value = T::ZERO;
+    let mut any_digits = false;
+    for (pos, c) in chars {
+        let digit = match c.to_digit(radix) {
+            Some(digit) => T::from_digit(digit),
+            None => match mode {
+                ParseMode::Strict => return Err(ParseIntError::InvalidDigit { pos }),
+                ParseMode::Atoi => break,
+            },
+        };
+        any_digits = true;
+
+        let next = value.checked_mul(radix_value).and_then(|shifted| {
             if negative {
-                -2147483648
+                shifted.checked_sub(digit)
             } else {
-                bound
+                shifted.checked_add(digit)
             }
+        });
+        value = match (next, mode) {
+            (Some(next), _) => next,
+            (None, ParseMode::Strict) => return Err(ParseIntError::Overflow),
+            // once it's out of range it stays clamped, but the digits still get read
+            (None, ParseMode::Atoi) if negative => T::MIN,
+            (None, ParseMode::Atoi) => T::MAX,
+        };
+    }
+
+    if !any_digits && mode == ParseMode::Strict {
+        return Err(ParseIntError::Empty);
+    }
+    Ok(value)
+}
+
+// Exact substring search. Every matcher is built once from a pattern and can then
+// be run over any number of haystacks. They all work on bytes, so offsets are byte
+// offsets; for a &str pass .as_bytes(), and since UTF-8 never matches part way
+// into a character, a match of a &str pattern always starts on a char boundary.
+//
+// An empty pattern matches at every offset from 0 to haystack.len() inclusive.
+
+// Whether a match may start inside the previous one. Searching "aaa" in "aaaaa"
+// finds 0, 1, 2 when overlapping and only 0 when not
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    Overlapping,
+    NonOverlapping,
+}
+
+impl MatchMode {
+    // where the next match is allowed to start after one at start
+    fn next_start(self, start: usize, pattern_len: usize) -> usize {
+        match self {
+            MatchMode::Overlapping => start + 1,
+            MatchMode::NonOverlapping => start + pattern_len.max(1),
         }
-    };
+    }
+}
+
+// Shared interface so callers can swap algorithms, including behind a Box<dyn Matcher>
+pub trait Matcher {
+    fn pattern(&self) -> &[u8];
 
-    parsed
+    // Lazily yields the offset of every match, in increasing order
+    fn find_iter<'a>(
+        &'a self,
+        haystack: &'a [u8],
+        mode: MatchMode,
+    ) -> Box<dyn Iterator<Item = usize> + 'a>;
+
+    fn find_first(&self, haystack: &[u8]) -> Option<usize> {
+        self.find_iter(haystack, MatchMode::Overlapping).next()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+// Everything an empty pattern matches
+fn empty_pattern_matches<'a>(haystack: &[u8]) -> Box<dyn Iterator<Item = usize> + 'a> {
+    Box::new(0..=haystack.len())
+}
 
-    #[test]
-    fn test_bracket_checker() {
-        let test1 = String::from("({})");
-        let test2 = String::from("({[]})");
-        let test3 = String::from("(){}[]");
-        let test4 = String::from("([]){}");
-        let test5 = String::from("({)}[]");
+// Knuth-Morris-Pratt. Precomputes, for every prefix of the pattern, the longest
+// proper prefix that's also a suffix of it. On a mismatch that says how much of
+// the match so far can be kept, so the haystack is read once, left to right, and
+// never backs up. O(n + m)
+pub struct Kmp {
+    pattern: Vec<u8>,
+    failure: Vec<usize>,
+}
 
-        assert!(bracket_checker(test1));
-        assert!(bracket_checker(test2));
-        assert!(bracket_checker(test3));
-        assert!(bracket_checker(test4));
-        assert!(!bracket_checker(test5));
+impl Kmp {
+    pub fn new(pattern: &[u8]) -> Self {
+        let mut failure = vec![0; pattern.len()];
+        let mut matched = 0;
+
+        for idx in 1..pattern.len() {
+            while matched > 0 && pattern[idx] != pattern[matched] {
+                matched = failure[matched - 1];
+            }
+            if pattern[idx] == pattern[matched] {
+                matched += 1;
+            }
+            failure[idx] = matched;
+        }
+
+        Kmp {
+            pattern: pattern.to_vec(),
+            failure,
+        }
     }
+}
 
-    #[test]
-    fn test_string_reversal() {
-        let test1 = String::from("Jello");
-        let test2 = String::from("mellow");
-        let test3 = String::from("purple");
-        let test4 = String::from("");
-        let test5 = String::from(" ");
+struct KmpMatches<'a> {
+    kmp: &'a Kmp,
+    haystack: &'a [u8],
+    mode: MatchMode,
+    pos: usize,
+    // how many pattern bytes the haystack just before pos matches
+    matched: usize,
+}
 
-        assert_eq!(reverse_string(test1), String::from("olleJ"));
-        assert_eq!(reverse_string(test2), String::from("wollem"));
-        assert_eq!(reverse_string(test3), String::from("elprup"));
-        assert_eq!(reverse_string(test4), String::from(""));
-        assert_eq!(reverse_string(test5), String::from(" "));
+impl Iterator for KmpMatches<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let pattern = &self.kmp.pattern;
+
+        while self.pos < self.haystack.len() {
+            let byte = self.haystack[self.pos];
+            self.pos += 1;
+
+            while self.matched > 0 && pattern[self.matched] != byte {
+                self.matched = self.kmp.failure[self.matched - 1];
+            }
+            if pattern[self.matched] == byte {
+                self.matched += 1;
+            }
+
+            if self.matched == pattern.len() {
+                // keep the longest border for overlapping matches, or start over
+                self.matched = match self.mode {
+                    MatchMode::Overlapping => self.kmp.failure[self.matched - 1],
+                    MatchMode::NonOverlapping => 0,
+                };
+                return Some(self.pos - pattern.len());
+            }
+        }
+
+        None
     }
+}
 
-    #[test]
-    fn test_number_reversal() {
-        let test1 = 32;
-        let test2 = 42;
-        let test3 = 1;
-        let test4 = -20;
-        let test5 = -30001;
+impl Matcher for Kmp {
+    fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
 
-        assert_eq!(reverse_integer(test1), 23);
-        assert_eq!(reverse_integer(test2), 24);
-        assert_eq!(reverse_integer(test3), 1);
-        assert_eq!(reverse_integer(test4), -2);
-        assert_eq!(reverse_integer(test5), -10003);
+    fn find_iter<'a>(
+        &'a self,
+        haystack: &'a [u8],
+        mode: MatchMode,
+    ) -> Box<dyn Iterator<Item = usize> + 'a> {
+        if self.pattern.is_empty() {
+            return empty_pattern_matches(haystack);
+        }
+
+        Box::new(KmpMatches {
+            kmp: self,
+            haystack,
+            mode,
+            pos: 0,
+            matched: 0,
+        })
     }
+}
 
-    #[test]
-    fn test_unique_char() {
-        let test1 = String::from("foobar");
-        let test2 = String::from("bazbar");
-        let test3 = String::from("loopzoop");
-        let test4 = String::from("zzzzzz");
-        let test5 = String::from("zzzzzzzooom");
+// Boyer-Moore-Horspool. Compares each window against the pattern, and when it
+// doesn't match, slides the window so the byte under its last position lines up
+// with that byte's last occurrence in the pattern (or past it, if it doesn't
+// occur). Often skips most of the haystack, but O(n * m) in the worst case
+pub struct Horspool {
+    pattern: Vec<u8>,
+    shift: [usize; 256],
+}
 
-        assert_eq!(first_unique_character(test1), 0);
-        assert_eq!(first_unique_character(test2), 2);
-        assert_eq!(first_unique_character(test3), 0);
-        assert_eq!(first_unique_character(test4), -1);
-        assert_eq!(first_unique_character(test5), 10);
+impl Horspool {
+    pub fn new(pattern: &[u8]) -> Self {
+        let mut shift = [pattern.len(); 256];
+        // the last byte is left out so a shift is never 0
+        for (idx, &byte) in pattern
+            .iter()
+            .enumerate()
+            .take(pattern.len().saturating_sub(1))
+        {
+            shift[byte as usize] = pattern.len() - 1 - idx;
+        }
+
+        Horspool {
+            pattern: pattern.to_vec(),
+            shift,
+        }
     }
+}
 
-    #[test]
-    fn test_anagram() {
-        let test1 = (String::from("loop"), String::from("pool"));
-        let test2 = (String::from("zing"), String::from("zang"));
-        let test3 = (String::from("gooloog"), String::from("loogoog"));
-        let test4 = (String::from("al"), String::from("la"));
-        let test5 = (String::from("po"), String::from("p"));
+struct HorspoolMatches<'a> {
+    horspool: &'a Horspool,
+    haystack: &'a [u8],
+    mode: MatchMode,
+    // start of the current window
+    pos: usize,
+}
 
-        assert!(is_anagram(test1.0, test1.1));
-        assert!(!is_anagram(test2.0, test2.1));
-        assert!(is_anagram(test3.0, test3.1));
-        assert!(is_anagram(test4.0, test4.1));
-        assert!(!is_anagram(test5.0, test5.1));
+impl Iterator for HorspoolMatches<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let pattern = &self.horspool.pattern;
+
+        while self.pos + pattern.len() <= self.haystack.len() {
+            let start = self.pos;
+            let last = self.haystack[start + pattern.len() - 1];
+
+            if self.haystack[start..start + pattern.len()] == pattern[..] {
+                self.pos = self.mode.next_start(start, pattern.len());
+                return Some(start);
+            }
+            self.pos += self.horspool.shift[last as usize];
+        }
+
+        None
     }
+}
 
-    #[test]
-    fn test_palindrome() {
-        let test1 = String::from("ado://oda");
-        let test2 = String::from("123zing gniz321");
-        let test3 = String::from(":laBodO /a/  odobal:");
-        let test4 = String::from("zoop loop");
-        let test5 = String::from("");
+impl Matcher for Horspool {
+    fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
 
-        assert!(is_palindrome(test1));
-        assert!(is_palindrome(test2));
-        assert!(is_palindrome(test3));
-        assert!(!is_palindrome(test4));
-        assert!(is_palindrome(test5));
+    fn find_iter<'a>(
+        &'a self,
+        haystack: &'a [u8],
+        mode: MatchMode,
+    ) -> Box<dyn Iterator<Item = usize> + 'a> {
+        if self.pattern.is_empty() {
+            return empty_pattern_matches(haystack);
+        }
+
+        Box::new(HorspoolMatches {
+            horspool: self,
+            haystack,
+            mode,
+            pos: 0,
+        })
     }
+}
 
-    #[test]
-    fn test_longest_substring() {
-        let test1 = String::from("avalanche");
-        let test2 = String::from("");
-        let test3 = String::from("foobar");
-        let test4 = String::from("lollipops");
-        let test5 = String::from("applesauce");
+const RK_BASE: u64 = 256;
+const RK_MODULUS: u64 = 1_000_000_007;
 
-        assert_eq!(longest_substring(test1), Some(String::from("lanche")));
-        assert_eq!(longest_substring(test2), Some(String::from("")));
-        assert_eq!(longest_substring(test3), Some(String::from("obar")));
-        assert_eq!(longest_substring(test4), Some(String::from("lipo")));
-        assert_eq!(longest_substring(test5), Some(String::from("plesauc")));
+// Rabin-Karp. Hashes the pattern and keeps a rolling hash of the current window,
+// which slides one byte along in O(1). Only windows whose hash matches get
+// compared byte by byte, so O(n + m) expected, O(n * m) if every hash collides
+pub struct RabinKarp {
+    pattern: Vec<u8>,
+    hash: u64,
+    // RK_BASE ^ (m - 1), the weight of the byte leaving the window
+    high: u64,
+}
+
+impl RabinKarp {
+    pub fn new(pattern: &[u8]) -> Self {
+        let high = (1..pattern.len()).fold(1, |acc, _| acc * RK_BASE % RK_MODULUS);
+
+        RabinKarp {
+            pattern: pattern.to_vec(),
+            hash: rk_hash(pattern),
+            high,
+        }
     }
+}
 
-    #[test]
-    fn test_string_to_int() {
-        let test1 = String::from("123");
-        let test2 = String::from("-123");
-        let test3 = String::from("-214748364899");
-        let test4 = String::from("2147483648999");
-        let test5 = String::from("1234 halp im stuck in a loop");
+fn rk_hash(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |acc, &byte| (acc * RK_BASE + byte as u64) % RK_MODULUS)
+}
 
-        assert_eq!(string_to_int(test1), 123);
-        assert_eq!(string_to_int(test2), -123);
-        assert_eq!(string_to_int(test3), -2147483648);
-        assert_eq!(string_to_int(test4), 2147483647);
-        assert_eq!(string_to_int(test5), 1234);
+struct RabinKarpMatches<'a> {
+    rabin_karp: &'a RabinKarp,
+    haystack: &'a [u8],
+    // earliest start the mode still allows
+    allowed: usize,
+    mode: MatchMode,
+    // start of the current window and its hash
+    pos: usize,
+    hash: u64,
+}
+
+impl Iterator for RabinKarpMatches<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let pattern = &self.rabin_karp.pattern;
+        let len = pattern.len();
+
+        while self.pos + len <= self.haystack.len() {
+            let start = self.pos;
+            let found = start >= self.allowed
+                && self.hash == self.rabin_karp.hash
+                && self.haystack[start..start + len] == pattern[..];
+
+            // roll the window one byte right, dropping haystack[start]
+            if start + len < self.haystack.len() {
+                let leaving = self.haystack[start] as u64 * self.rabin_karp.high % RK_MODULUS;
+                let entering = self.haystack[start + len] as u64;
+                self.hash = ((self.hash + RK_MODULUS - leaving) * RK_BASE + entering) % RK_MODULUS;
+            }
+            self.pos += 1;
+
+            if found {
+                self.allowed = self.mode.next_start(start, len);
+                return Some(start);
+            }
+        }
+
+        None
+    }
+}
+
+impl Matcher for RabinKarp {
+    fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    fn find_iter<'a>(
+        &'a self,
+        haystack: &'a [u8],
+        mode: MatchMode,
+    ) -> Box<dyn Iterator<Item = usize> + 'a> {
+        if self.pattern.is_empty() {
+            return empty_pattern_matches(haystack);
+        }
+
+        let hash = haystack.get(..self.pattern.len()).map_or(0, rk_hash);
+        Box::new(RabinKarpMatches {
+            rabin_karp: self,
+            haystack,
+            allowed: 0,
+            mode,
+            pos: 0,
+            hash,
+        })
+    }
+}
+
+// Z-algorithm. z[i] is the length of the longest common prefix of the pattern and
+// the pattern starting at i. Running the same idea along the haystack gives, for
+// every offset, how much of the pattern matches there, reusing earlier matches so
+// every byte is compared a constant number of times. O(n + m)
+pub struct ZMatcher {
+    pattern: Vec<u8>,
+    z: Vec<usize>,
+}
+
+impl ZMatcher {
+    pub fn new(pattern: &[u8]) -> Self {
+        ZMatcher {
+            pattern: pattern.to_vec(),
+            z: z_array(pattern),
+        }
+    }
+}
+
+fn z_array(bytes: &[u8]) -> Vec<usize> {
+    let mut z = vec![0; bytes.len()];
+    if let Some(first) = z.first_mut() {
+        *first = bytes.len();
+    }
+
+    // [left, right) is the rightmost window seen so far that matches a prefix
+    let mut left = 0;
+    let mut right = 0;
+    for idx in 1..bytes.len() {
+        if idx < right {
+            z[idx] = (right - idx).min(z[idx - left]);
+        }
+        while idx + z[idx] < bytes.len() && bytes[z[idx]] == bytes[idx + z[idx]] {
+            z[idx] += 1;
+        }
+        if idx + z[idx] > right {
+            left = idx;
+            right = idx + z[idx];
+        }
+    }
+
+    z
+}
+
+struct ZMatches<'a> {
+    matcher: &'a ZMatcher,
+    haystack: &'a [u8],
+    allowed: usize,
+    mode: MatchMode,
+    pos: usize,
+    // haystack[left..right) equals pattern[..right - left]
+    left: usize,
+    right: usize,
+}
+
+impl Iterator for ZMatches<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let pattern = &self.matcher.pattern;
+
+        while self.pos + pattern.len() <= self.haystack.len() {
+            let start = self.pos;
+            self.pos += 1;
+
+            // inside the window the pattern's own z values say how far the match goes,
+            // unless it runs up to the window's edge and has to be extended by hand
+            let known = if start < self.right {
+                self.matcher.z[start - self.left]
+            } else {
+                0
+            };
+            let matched = if start < self.right && known < self.right - start {
+                known
+            } else {
+                let mut matched = self.right.saturating_sub(start);
+                while matched < pattern.len()
+                    && start + matched < self.haystack.len()
+                    && self.haystack[start + matched] == pattern[matched]
+                {
+                    matched += 1;
+                }
+                self.left = start;
+                self.right = start + matched;
+                matched
+            };
+
+            if matched == pattern.len() && start >= self.allowed {
+                self.allowed = self.mode.next_start(start, pattern.len());
+                return Some(start);
+            }
+        }
+
+        None
+    }
+}
+
+impl Matcher for ZMatcher {
+    fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    fn find_iter<'a>(
+        &'a self,
+        haystack: &'a [u8],
+        mode: MatchMode,
+    ) -> Box<dyn Iterator<Item = usize> + 'a> {
+        if self.pattern.is_empty() {
+            return empty_pattern_matches(haystack);
+        }
+
+        Box::new(ZMatches {
+            matcher: self,
+            haystack,
+            allowed: 0,
+            mode,
+            pos: 0,
+            left: 0,
+            right: 0,
+        })
+    }
+}
+
+// Aho-Corasick: finds every occurrence of many patterns in one pass over the
+// haystack. The patterns go into a trie, and every trie node gets a failure link to
+// the node for its longest proper suffix that's also in the trie, like Kmp's
+// failure table but spread over a whole set of patterns. Searching follows trie
+// edges, falls back along failure links on a miss, and reads each byte once, so it
+// takes O(n + number of matches) no matter how many patterns there are.
+//
+// Like the matchers above it works on bytes and reports byte offsets. Empty
+// patterns still get an id but never match.
+
+// Which matches a search reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    // Every occurrence of every pattern, overlapping ones included, in the order
+    // they end. Of several ending at the same place the longest comes first
+    All,
+    // Non-overlapping matches scanning left to right: of the matches starting
+    // furthest left, take the longest (the lowest pattern id on a tie), then
+    // carry on after its end
+    LeftmostLongest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternMatch {
+    // index of the pattern in the list the automaton was built from
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl PatternMatch {
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+const AC_ROOT: usize = 0;
+// how much of a Read source is pulled in at a time
+const AC_READ_CHUNK: usize = 8 * 1024;
+
+struct AcNode {
+    children: BTreeMap<u8, usize>,
+    fail: usize,
+    // length of the path from the root, i.e. how many bytes this node has matched
+    depth: usize,
+    // patterns that end exactly at this node
+    outputs: Vec<usize>,
+    // nearest node down the failure chain that has outputs, so collecting every
+    // pattern that ends here skips the nodes that don't
+    output_link: Option<usize>,
+}
+
+impl AcNode {
+    fn new(depth: usize) -> Self {
+        AcNode {
+            children: BTreeMap::new(),
+            fail: AC_ROOT,
+            depth,
+            outputs: Vec::new(),
+            output_link: None,
+        }
+    }
+}
+
+pub struct AhoCorasick {
+    nodes: Vec<AcNode>,
+    pattern_lens: Vec<usize>,
+    ascii_case_insensitive: bool,
+}
+
+impl AhoCorasick {
+    pub fn new<I, P>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        Self::build(patterns, false)
+    }
+
+    // Treats ASCII letters as equal regardless of case. Other bytes, including
+    // non-ASCII letters, still have to match exactly
+    pub fn with_ascii_case_insensitive<I, P>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        Self::build(patterns, true)
+    }
+
+    fn build<I, P>(patterns: I, ascii_case_insensitive: bool) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        let mut automaton = AhoCorasick {
+            nodes: vec![AcNode::new(0)],
+            pattern_lens: Vec::new(),
+            ascii_case_insensitive,
+        };
+
+        for (id, pattern) in patterns.into_iter().enumerate() {
+            let pattern = pattern.as_ref();
+            automaton.pattern_lens.push(pattern.len());
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let mut node = AC_ROOT;
+            for &byte in pattern {
+                let byte = automaton.fold(byte);
+                node = match automaton.nodes[node].children.get(&byte) {
+                    Some(&child) => child,
+                    None => {
+                        let child = automaton.nodes.len();
+                        let depth = automaton.nodes[node].depth + 1;
+                        automaton.nodes.push(AcNode::new(depth));
+                        automaton.nodes[node].children.insert(byte, child);
+                        child
+                    }
+                };
+            }
+            automaton.nodes[node].outputs.push(id);
+        }
+
+        automaton.link_failures();
+        automaton
+    }
+
+    // Breadth first, so a node's failure target is always shallower and already linked
+    fn link_failures(&mut self) {
+        let mut queue: VecDeque<usize> = self.nodes[AC_ROOT].children.values().copied().collect();
+
+        while let Some(node) = queue.pop_front() {
+            let children: Vec<(u8, usize)> = self.nodes[node]
+                .children
+                .iter()
+                .map(|(&byte, &child)| (byte, child))
+                .collect();
+
+            for (byte, child) in children {
+                // the child's suffix is the parent's suffix extended by the same byte,
+                // or shorter ones down the parent's failure chain
+                let fail = if node == AC_ROOT {
+                    AC_ROOT
+                } else {
+                    self.step(self.nodes[node].fail, byte)
+                };
+
+                self.nodes[child].fail = fail;
+                self.nodes[child].output_link = if self.nodes[fail].outputs.is_empty() {
+                    self.nodes[fail].output_link
+                } else {
+                    Some(fail)
+                };
+                queue.push_back(child);
+            }
+        }
+    }
+
+    fn fold(&self, byte: u8) -> u8 {
+        if self.ascii_case_insensitive {
+            byte.to_ascii_lowercase()
+        } else {
+            byte
+        }
+    }
+
+    // The node reached by reading byte from node, following failure links until
+    // some suffix can be extended by it
+    fn step(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&child) = self.nodes[node].children.get(&byte) {
+                return child;
+            }
+            if node == AC_ROOT {
+                return AC_ROOT;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+
+    pub fn pattern_count(&self) -> usize {
+        self.pattern_lens.len()
+    }
+
+    // Searches a &str, String, &[u8] or anything else that's bytes
+    pub fn find_iter<'a, H>(&'a self, haystack: &'a H, kind: MatchKind) -> AhoCorasickMatches<'a>
+    where
+        H: AsRef<[u8]> + ?Sized,
+    {
+        AhoCorasickMatches {
+            haystack: haystack.as_ref(),
+            idx: 0,
+            search: AcSearch::new(self, kind),
+        }
+    }
+
+    // Searches bytes as they're read, holding only one chunk of the source in memory
+    // at a time. Offsets count from the start of the stream. A read error is passed
+    // on and ends the iteration
+    pub fn stream_find_iter<R: Read>(
+        &self,
+        reader: R,
+        kind: MatchKind,
+    ) -> AhoCorasickStreamMatches<'_, R> {
+        AhoCorasickStreamMatches {
+            reader,
+            buffer: vec![0; AC_READ_CHUNK],
+            idx: 0,
+            filled: 0,
+            done: false,
+            search: AcSearch::new(self, kind),
+        }
+    }
+}
+
+// The search state, fed one byte at a time so slices and streams share it
+struct AcSearch<'a> {
+    automaton: &'a AhoCorasick,
+    kind: MatchKind,
+    node: usize,
+    // bytes read so far
+    pos: usize,
+    // matches ready to hand out
+    ready: VecDeque<PatternMatch>,
+    // leftmost-longest only: matches that a later, longer one might still beat,
+    // and the end of the last match reported, which later ones can't overlap
+    pending: Vec<PatternMatch>,
+    last_end: usize,
+    finished: bool,
+}
+
+impl<'a> AcSearch<'a> {
+    fn new(automaton: &'a AhoCorasick, kind: MatchKind) -> Self {
+        AcSearch {
+            automaton,
+            kind,
+            node: AC_ROOT,
+            pos: 0,
+            ready: VecDeque::new(),
+            pending: Vec::new(),
+            last_end: 0,
+            finished: false,
+        }
+    }
+
+    fn feed(&mut self, byte: u8) {
+        let automaton = self.automaton;
+        self.node = automaton.step(self.node, automaton.fold(byte));
+        self.pos += 1;
+
+        // every pattern ending here, longest first
+        let mut with_output = if automaton.nodes[self.node].outputs.is_empty() {
+            automaton.nodes[self.node].output_link
+        } else {
+            Some(self.node)
+        };
+        while let Some(node) = with_output {
+            for &pattern in &automaton.nodes[node].outputs {
+                let found = PatternMatch {
+                    pattern,
+                    start: self.pos - automaton.pattern_lens[pattern],
+                    end: self.pos,
+                };
+                match self.kind {
+                    MatchKind::All => self.ready.push_back(found),
+                    MatchKind::LeftmostLongest => {
+                        if found.start >= self.last_end {
+                            self.pending.push(found);
+                        }
+                    }
+                }
+            }
+            with_output = automaton.nodes[node].output_link;
+        }
+
+        if self.kind == MatchKind::LeftmostLongest {
+            // the current node's depth is the longest stretch of recent input that could
+            // still grow into a match, so nothing found later can start before this
+            let earliest_start = self.pos - automaton.nodes[self.node].depth;
+            self.settle(earliest_start);
+        }
+    }
+
+    fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            self.settle(usize::MAX);
+        }
+    }
+
+    // Reports pending matches once no match still to come can start at or before them
+    fn settle(&mut self, earliest_start: usize) {
+        loop {
+            let last_end = self.last_end;
+            self.pending.retain(|found| found.start >= last_end);
+
+            let best = self.pending.iter().copied().min_by(|a, b| {
+                a.start
+                    .cmp(&b.start)
+                    .then(b.end.cmp(&a.end))
+                    .then(a.pattern.cmp(&b.pattern))
+            });
+            match best {
+                Some(found) if found.start < earliest_start => {
+                    self.ready.push_back(found);
+                    self.last_end = found.end;
+                }
+                _ => return,
+            }
+        }
+    }
+}
+
+pub struct AhoCorasickMatches<'a> {
+    haystack: &'a [u8],
+    idx: usize,
+    search: AcSearch<'a>,
+}
+
+impl Iterator for AhoCorasickMatches<'_> {
+    type Item = PatternMatch;
+
+    fn next(&mut self) -> Option<PatternMatch> {
+        loop {
+            if let Some(found) = self.search.ready.pop_front() {
+                return Some(found);
+            }
+
+            match self.haystack.get(self.idx) {
+                Some(&byte) => {
+                    self.search.feed(byte);
+                    self.idx += 1;
+                }
+                None if !self.search.finished => self.search.finish(),
+                None => return None,
+            }
+        }
+    }
+}
+
+pub struct AhoCorasickStreamMatches<'a, R> {
+    reader: R,
+    buffer: Vec<u8>,
+    // the unsearched part of the buffer is buffer[idx..filled]
+    idx: usize,
+    filled: usize,
+    done: bool,
+    search: AcSearch<'a>,
+}
+
+impl<R: Read> Iterator for AhoCorasickStreamMatches<'_, R> {
+    type Item = io::Result<PatternMatch>;
+
+    fn next(&mut self) -> Option<io::Result<PatternMatch>> {
+        loop {
+            if let Some(found) = self.search.ready.pop_front() {
+                return Some(Ok(found));
+            }
+            if self.done {
+                return None;
+            }
+
+            if self.idx < self.filled {
+                self.search.feed(self.buffer[self.idx]);
+                self.idx += 1;
+                continue;
+            }
+
+            match self.reader.read(&mut self.buffer) {
+                Ok(0) => {
+                    self.search.finish();
+                    self.done = true;
+                }
+                Ok(filled) => {
+                    self.idx = 0;
+                    self.filled = filled;
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+// Suffix array: the start offsets of every suffix of the text, sorted in
+// lexicographic order of the suffixes. Any substring is a prefix of some suffixes,
+// and those suffixes sit next to each other in the array, so a lot of substring
+// questions become binary searches or scans over it. The LCP array alongside it
+// holds, for each suffix, the length of the prefix it shares with the one sorted
+// before it.
+//
+// The array is built with SA-IS (induced sorting) and the LCP array with Kasai's
+// algorithm, both O(n). Offsets are stored as u32 so big inputs stay affordable:
+// about 9 bytes of memory per byte of text, so tens of MB is fine. Texts must be
+// shorter than u32::MAX bytes. Like the matchers it works on bytes.
+pub struct SuffixArray {
+    text: Vec<u8>,
+    suffixes: Vec<u32>,
+    lcp: Vec<u32>,
+}
+
+impl SuffixArray {
+    pub fn new<T: AsRef<[u8]> + ?Sized>(text: &T) -> Self {
+        let text = text.as_ref().to_vec();
+        assert!(
+            text.len() < u32::MAX as usize,
+            "text is too long for a suffix array"
+        );
+
+        let suffixes = sa_is(&text, u8::MAX as usize);
+        let lcp = kasai(&text, &suffixes);
+        SuffixArray {
+            text,
+            suffixes,
+            lcp,
+        }
+    }
+
+    pub fn text(&self) -> &[u8] {
+        &self.text
+    }
+
+    // Starts of the suffixes in sorted order
+    pub fn suffixes(&self) -> &[u32] {
+        &self.suffixes
+    }
+
+    // lcp[i] is the length of the common prefix of the suffixes at suffixes[i - 1]
+    // and suffixes[i]. lcp[0] is always 0
+    pub fn lcp(&self) -> &[u32] {
+        &self.lcp
+    }
+
+    // The range of suffixes() whose suffixes start with pattern
+    fn pattern_range(&self, pattern: &[u8]) -> Range<usize> {
+        // only the first pattern.len() bytes of each suffix matter
+        let prefix = |&start: &u32| {
+            let start = start as usize;
+            &self.text[start..(start + pattern.len()).min(self.text.len())]
+        };
+
+        let low = partition_point(&self.suffixes, |start| prefix(start) < pattern);
+        let high = partition_point(&self.suffixes, |start| prefix(start) <= pattern);
+        low..high
+    }
+
+    // How many times pattern occurs, overlapping occurrences included. O(m log n).
+    // As with the matchers, the empty pattern occurs at all len + 1 offsets
+    pub fn count_occurrences(&self, pattern: &[u8]) -> usize {
+        if pattern.is_empty() {
+            return self.text.len() + 1;
+        }
+        self.pattern_range(pattern).len()
+    }
+
+    // Offsets of every occurrence of pattern, in increasing order
+    pub fn occurrences(&self, pattern: &[u8]) -> Vec<usize> {
+        if pattern.is_empty() {
+            return (0..=self.text.len()).collect();
+        }
+
+        let mut found: Vec<usize> = self.suffixes[self.pattern_range(pattern)]
+            .iter()
+            .map(|&start| start as usize)
+            .collect();
+        found.sort_unstable();
+        found
+    }
+
+    // The longest substring that occurs at least twice (the occurrences may overlap),
+    // or None if no byte repeats. Of several, the one that sorts first. The longest
+    // repeat is always the common prefix of two neighbouring suffixes, so it's the
+    // biggest LCP value
+    pub fn longest_repeated_substring(&self) -> Option<&[u8]> {
+        let (idx, &len) = self
+            .lcp
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, &len)| len)?;
+        if len == 0 {
+            return None;
+        }
+
+        let start = self.suffixes[idx] as usize;
+        Some(&self.text[start..start + len as usize])
+    }
+
+    // Number of different non-empty substrings. Each suffix contributes all of its
+    // prefixes except the ones it shares with the suffix sorted before it
+    pub fn distinct_substrings(&self) -> u64 {
+        let len = self.text.len() as u64;
+        let shared: u64 = self.lcp.iter().map(|&len| len as u64).sum();
+        len * (len + 1) / 2 - shared
+    }
+}
+
+// The longest substring of both a and b, or an empty slice if they share no bytes.
+// Builds one suffix array over a, a separator and b; the separator can't be part
+// of any common prefix, so the answer is the biggest LCP between a neighbouring
+// suffix of a and suffix of b. O(a + b)
+pub fn longest_common_substring<'a>(a: &'a [u8], b: &[u8]) -> &'a [u8] {
+    assert!(
+        a.len() + b.len() < u32::MAX as usize,
+        "text is too long for a suffix array"
+    );
+
+    // bytes keep their values, and 256 is a symbol no byte can be
+    let separator = u8::MAX as u32 + 1;
+    let joined: Vec<u32> = a
+        .iter()
+        .map(|&byte| byte as u32)
+        .chain(Some(separator))
+        .chain(b.iter().map(|&byte| byte as u32))
+        .collect();
+
+    let suffixes = sa_is(&joined, separator as usize);
+    let lcp = kasai(&joined, &suffixes);
+
+    let in_a = |start: u32| (start as usize) < a.len();
+    let mut best = 0..0;
+    for idx in 1..suffixes.len() {
+        let (prev, curr) = (suffixes[idx - 1], suffixes[idx]);
+        let len = lcp[idx] as usize;
+
+        // one suffix from each side; the separator's own suffix has an LCP of 0
+        if in_a(prev) != in_a(curr) && len > best.len() {
+            let start = prev.min(curr) as usize;
+            best = start..start + len;
+        }
+    }
+
+    &a[best]
+}
+
+// Alphabets the suffix array can be built over: bytes for text, and u32 for the
+// reduced problems SA-IS recurses on (and texts with an extra separator)
+trait Symbol: Copy {
+    fn rank(self) -> usize;
+}
+
+impl Symbol for u8 {
+    fn rank(self) -> usize {
+        self as usize
+    }
+}
+
+impl Symbol for u32 {
+    fn rank(self) -> usize {
+        self as usize
+    }
+}
+
+// marks a slot of the suffix array that hasn't been filled in yet
+const SA_EMPTY: u32 = u32::MAX;
+
+// SA-IS. Every suffix is S-type if it's smaller than the one after it and L-type
+// if it's bigger. An LMS suffix is an S-type one right after an L-type one. Once
+// the LMS suffixes are sorted, one pass left to right places every L-type suffix
+// and one pass right to left every S-type one ("inducing" their order). Sorting
+// the LMS suffixes is a smaller instance of the same problem (at most half the
+// size), so it recurses, which keeps the whole thing O(n).
+//
+// upper is the largest symbol rank that can occur
+fn sa_is<S: Symbol>(text: &[S], upper: usize) -> Vec<u32> {
+    let len = text.len();
+    match len {
+        0 => return Vec::new(),
+        1 => return vec![0],
+        2 => {
+            return if text[0].rank() < text[1].rank() {
+                vec![0, 1]
+            } else {
+                vec![1, 0]
+            };
+        }
+        _ => {}
+    }
+
+    // the last suffix counts as L-type
+    let mut s_type = vec![false; len];
+    for idx in (0..len - 1).rev() {
+        let (curr, next) = (text[idx].rank(), text[idx + 1].rank());
+        s_type[idx] = if curr == next {
+            s_type[idx + 1]
+        } else {
+            curr < next
+        };
+    }
+
+    // Every symbol gets a bucket in the array, L-type suffixes at the front of it
+    // and S-type ones at the back. bucket_l[c] is where c's bucket starts and
+    // bucket_s[c] where its S-type part starts
+    let mut bucket_l = vec![0u32; upper + 1];
+    let mut bucket_s = vec![0u32; upper + 1];
+    for (idx, symbol) in text.iter().enumerate() {
+        if s_type[idx] {
+            // S-type symbols are smaller than some later one, so never the largest
+            bucket_l[symbol.rank() + 1] += 1;
+        } else {
+            bucket_s[symbol.rank()] += 1;
+        }
+    }
+    for symbol in 0..=upper {
+        bucket_s[symbol] += bucket_l[symbol];
+        if symbol < upper {
+            bucket_l[symbol + 1] += bucket_s[symbol];
+        }
+    }
+
+    // lms_index[i] is the position of i among the LMS suffixes
+    let mut lms_index = vec![SA_EMPTY; len + 1];
+    let mut lms = Vec::new();
+    for idx in 1..len {
+        if !s_type[idx - 1] && s_type[idx] {
+            lms_index[idx] = lms.len() as u32;
+            lms.push(idx as u32);
+        }
+    }
+
+    // Sorting just the first stretch of each LMS suffix (up to the next LMS
+    // position) is enough to give each of them a rank for the recursion
+    let mut suffixes = vec![SA_EMPTY; len];
+    induce(text, &mut suffixes, &s_type, &lms, &bucket_l, &bucket_s);
+
+    if !lms.is_empty() {
+        let sorted_lms: Vec<u32> = suffixes
+            .iter()
+            .copied()
+            .filter(|&start| start != SA_EMPTY && lms_index[start as usize] != SA_EMPTY)
+            .collect();
+        let lms_end = |start: usize| {
+            lms.get(lms_index[start] as usize + 1)
+                .map_or(len, |&end| end as usize)
+        };
+
+        // Name each stretch, equal stretches getting the same name, and sort the
+        // LMS suffixes by the string of names that follows them
+        let mut reduced = vec![0u32; lms.len()];
+        let mut name = 0;
+        for pair in sorted_lms.windows(2) {
+            let (mut left, mut right) = (pair[0] as usize, pair[1] as usize);
+            let (left_end, right_end) = (lms_end(left), lms_end(right));
+
+            let mut same = left_end - left == right_end - right;
+            if same {
+                while left < left_end && text[left].rank() == text[right].rank() {
+                    left += 1;
+                    right += 1;
+                }
+                same = left < len && right < len && text[left].rank() == text[right].rank();
+            }
+
+            if !same {
+                name += 1;
+            }
+            reduced[lms_index[pair[1] as usize] as usize] = name;
+        }
+
+        let reduced_order = sa_is(&reduced, name as usize);
+        let sorted_lms: Vec<u32> = reduced_order.iter().map(|&idx| lms[idx as usize]).collect();
+        induce(
+            text,
+            &mut suffixes,
+            &s_type,
+            &sorted_lms,
+            &bucket_l,
+            &bucket_s,
+        );
+    }
+
+    suffixes
+}
+
+// Places the LMS suffixes in the given order at the back of their buckets, then
+// induces where every other suffix goes from them
+fn induce<S: Symbol>(
+    text: &[S],
+    suffixes: &mut [u32],
+    s_type: &[bool],
+    lms: &[u32],
+    bucket_l: &[u32],
+    bucket_s: &[u32],
+) {
+    let len = text.len();
+    suffixes.fill(SA_EMPTY);
+
+    let mut next = bucket_s.to_vec();
+    for &start in lms {
+        let symbol = text[start as usize].rank();
+        suffixes[next[symbol] as usize] = start;
+        next[symbol] += 1;
+    }
+
+    // L-type suffixes, front to back: if suffix i is placed, suffix i - 1 goes
+    // next in its bucket when it's L-type
+    next.copy_from_slice(bucket_l);
+    let symbol = text[len - 1].rank();
+    suffixes[next[symbol] as usize] = (len - 1) as u32;
+    next[symbol] += 1;
+    for idx in 0..len {
+        let start = suffixes[idx];
+        if start != SA_EMPTY && start > 0 && !s_type[start as usize - 1] {
+            let symbol = text[start as usize - 1].rank();
+            suffixes[next[symbol] as usize] = start - 1;
+            next[symbol] += 1;
+        }
+    }
+
+    // S-type suffixes, back to front, filling each bucket from its end (which is
+    // where the next bucket starts)
+    next.copy_from_slice(bucket_l);
+    for idx in (0..len).rev() {
+        let start = suffixes[idx];
+        if start != SA_EMPTY && start > 0 && s_type[start as usize - 1] {
+            let symbol = text[start as usize - 1].rank();
+            next[symbol + 1] -= 1;
+            suffixes[next[symbol + 1] as usize] = start - 1;
+        }
+    }
+}
+
+// Kasai's algorithm. Going through the suffixes in text order, the common prefix
+// with the previous suffix in sorted order can shrink by at most one from one
+// suffix to the next, so the comparisons add up to O(n)
+fn kasai<S: Symbol>(text: &[S], suffixes: &[u32]) -> Vec<u32> {
+    let len = text.len();
+    let mut rank = vec![0u32; len];
+    for (idx, &start) in suffixes.iter().enumerate() {
+        rank[start as usize] = idx as u32;
+    }
+
+    let mut lcp = vec![0u32; len];
+    let mut matched = 0;
+    for start in 0..len {
+        let idx = rank[start] as usize;
+        if idx == 0 {
+            matched = 0;
+            continue;
+        }
+
+        let prev = suffixes[idx - 1] as usize;
+        while start + matched < len
+            && prev + matched < len
+            && text[start + matched].rank() == text[prev + matched].rank()
+        {
+            matched += 1;
+        }
+        lcp[idx] = matched as u32;
+        matched = matched.saturating_sub(1);
+    }
+
+    lcp
+}
+
+// Trie (prefix tree) over any small copyable key unit: char for text, u8 for bytes,
+// anything else that's Ord. Every key carries a u64 weight, which top_k ranks by.
+// Keys are passed as iterators of units, e.g. "cat".chars() or b"cat".iter().copied().
+//
+// Like ArenaList, nodes live in one Vec and refer to each other by u32 index, with
+// removed nodes going on a free list to be reused. Each node keeps its children
+// sorted in a small Vec, which is a lot lighter than a map per node, and because
+// it's all Vecs memory_usage() and node_memory() can account for it exactly.
+pub struct Trie<K> {
+    nodes: Vec<TrieNode<K>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+const TRIE_ROOT: u32 = 0;
+
+struct TrieNode<K> {
+    // sorted by key unit
+    children: Vec<(K, u32)>,
+    // Some if a key ends here
+    weight: Option<u64>,
+    // largest weight of any key at or under this node, so top_k can go straight
+    // for the heaviest subtree
+    best: Option<u64>,
+}
+
+impl<K> TrieNode<K> {
+    fn new() -> Self {
+        TrieNode {
+            children: Vec::new(),
+            weight: None,
+            best: None,
+        }
+    }
+}
+
+// What a Trie's nodes take up in total. Node slots that are free for reuse are
+// included, since the memory is still held. node_memory() breaks it down by node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrieMemory {
+    // nodes in use
+    pub nodes: usize,
+    // the node slots themselves, in use or free
+    pub node_bytes: usize,
+    // the child lists hanging off the nodes
+    pub child_bytes: usize,
+}
+
+impl TrieMemory {
+    pub fn total_bytes(&self) -> usize {
+        self.node_bytes + self.child_bytes
+    }
+
+    pub fn average_node_bytes(&self) -> f64 {
+        self.total_bytes() as f64 / self.nodes as f64
+    }
+}
+
+// What one node in use takes up: its own slot plus its child list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieNodeMemory<K> {
+    // the prefix that leads to the node, empty for the root
+    pub key: Vec<K>,
+    pub children: usize,
+    pub bytes: usize,
+}
+
+impl<K: Ord + Copy> Trie<K> {
+    pub fn new() -> Self {
+        Trie {
+            nodes: vec![TrieNode::new()],
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    // number of keys
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn child(&self, node: u32, unit: K) -> Option<u32> {
+        let children = &self.nodes[node as usize].children;
+        children
+            .binary_search_by(|(key, _)| key.cmp(&unit))
+            .ok()
+            .map(|idx| children[idx].1)
+    }
+
+    // The node reached by following key from the root, if there is one
+    fn find_node<I: IntoIterator<Item = K>>(&self, key: I) -> Option<u32> {
+        key.into_iter()
+            .try_fold(TRIE_ROOT, |node, unit| self.child(node, unit))
+    }
+
+    fn alloc(&mut self) -> u32 {
+        match self.free.pop() {
+            Some(node) => node,
+            None => {
+                self.nodes.push(TrieNode::new());
+                (self.nodes.len() - 1) as u32
+            }
+        }
+    }
+
+    // Adds key with the given weight, or sets its weight if it's already there.
+    // Returns the old weight
+    pub fn insert<I: IntoIterator<Item = K>>(&mut self, key: I, weight: u64) -> Option<u64> {
+        let mut path = vec![TRIE_ROOT];
+        for unit in key {
+            let node = *path.last().unwrap();
+            let children = &self.nodes[node as usize].children;
+
+            let next = match children.binary_search_by(|(key, _)| key.cmp(&unit)) {
+                Ok(idx) => children[idx].1,
+                Err(idx) => {
+                    let child = self.alloc();
+                    self.nodes[node as usize]
+                        .children
+                        .insert(idx, (unit, child));
+                    child
+                }
+            };
+            path.push(next);
+        }
+
+        let end = *path.last().unwrap() as usize;
+        let old = self.nodes[end].weight.replace(weight);
+        if old.is_none() {
+            self.len += 1;
+        }
+        self.update_best(&path);
+        old
+    }
+
+    // Removes key and returns its weight. Nodes left with no keys under them are
+    // freed on the way back up
+    pub fn remove<I: IntoIterator<Item = K>>(&mut self, key: I) -> Option<u64> {
+        // each node on the way down, and the unit that led to it
+        let mut path = vec![(TRIE_ROOT, None)];
+        for unit in key {
+            let node = path.last().unwrap().0;
+            path.push((self.child(node, unit)?, Some(unit)));
+        }
+
+        let end = path.last().unwrap().0 as usize;
+        let old = self.nodes[end].weight.take()?;
+        self.len -= 1;
+
+        // unlink nodes bottom up while they're empty, never the root
+        while path.len() > 1 {
+            let (node, unit) = *path.last().unwrap();
+            let trie_node = &self.nodes[node as usize];
+            if trie_node.weight.is_some() || !trie_node.children.is_empty() {
+                break;
+            }
+
+            path.pop();
+            let parent = path.last().unwrap().0 as usize;
+            let unit = unit.unwrap();
+            self.nodes[parent].children.retain(|&(key, _)| key != unit);
+
+            // give back the child list's memory, the slot itself gets reused
+            self.nodes[node as usize] = TrieNode::new();
+            self.free.push(node);
+        }
+
+        let path: Vec<u32> = path.into_iter().map(|(node, _)| node).collect();
+        self.update_best(&path);
+        Some(old)
+    }
+
+    // Recomputes best along a root to node path, bottom up
+    fn update_best(&mut self, path: &[u32]) {
+        for &node in path.iter().rev() {
+            let trie_node = &self.nodes[node as usize];
+            let best = trie_node
+                .children
+                .iter()
+                .filter_map(|&(_, child)| self.nodes[child as usize].best)
+                .chain(trie_node.weight)
+                .max();
+            self.nodes[node as usize].best = best;
+        }
+    }
+
+    pub fn contains<I: IntoIterator<Item = K>>(&self, key: I) -> bool {
+        self.weight(key).is_some()
+    }
+
+    pub fn weight<I: IntoIterator<Item = K>>(&self, key: I) -> Option<u64> {
+        self.nodes[self.find_node(key)? as usize].weight
+    }
+
+    // Whether any key starts with prefix. Every key starts with the empty prefix,
+    // so that's true unless the trie is empty
+    pub fn starts_with<I: IntoIterator<Item = K>>(&self, prefix: I) -> bool {
+        self.find_node(prefix)
+            .is_some_and(|node| self.nodes[node as usize].best.is_some())
+    }
+
+    // Every key starting with prefix and its weight, in sorted key order
+    pub fn keys_with_prefix<I: IntoIterator<Item = K>>(&self, prefix: I) -> TriePrefixIter<'_, K> {
+        let prefix: Vec<K> = prefix.into_iter().collect();
+        let stack = match self.find_node(prefix.iter().copied()) {
+            Some(node) => vec![(node, prefix)],
+            None => Vec::new(),
+        };
+
+        TriePrefixIter { trie: self, stack }
+    }
+
+    // The k heaviest keys starting with prefix, heaviest first, ties in key order.
+    // Searches best first using each node's best weight, so it only opens up the
+    // subtrees that can still hold one of the top k rather than the whole prefix
+    pub fn top_k<I: IntoIterator<Item = K>>(&self, prefix: I, k: usize) -> Vec<(Vec<K>, u64)> {
+        let prefix: Vec<K> = prefix.into_iter().collect();
+        let mut found = Vec::new();
+        let mut heap = BinaryHeap::new();
+
+        if let Some(node) = self.find_node(prefix.iter().copied()) {
+            if let Some(best) = self.nodes[node as usize].best {
+                heap.push(Completion {
+                    weight: best,
+                    key: Reverse(prefix),
+                    node: Some(node),
+                });
+            }
+        }
+
+        while found.len() < k {
+            let Some(Completion { weight, key, node }) = heap.pop() else {
+                break;
+            };
+            let Some(node) = node else {
+                found.push((key.0, weight));
+                continue;
+            };
+
+            // a subtree opens up into its own key and its children's subtrees. Every
+            // key under the subtree sorts after its prefix, so ties stay in key order
+            let trie_node = &self.nodes[node as usize];
+            if let Some(weight) = trie_node.weight {
+                heap.push(Completion {
+                    weight,
+                    key: Reverse(key.0.clone()),
+                    node: None,
+                });
+            }
+            for &(unit, child) in &trie_node.children {
+                if let Some(best) = self.nodes[child as usize].best {
+                    let mut child_key = key.0.clone();
+                    child_key.push(unit);
+                    heap.push(Completion {
+                        weight: best,
+                        key: Reverse(child_key),
+                        node: Some(child),
+                    });
+                }
+            }
+        }
+
+        found
+    }
+
+    // Of the keys that are a prefix of key, the longest one, as its length and
+    // weight. This is the lookup a routing table does with address prefixes
+    pub fn longest_prefix_match<I: IntoIterator<Item = K>>(&self, key: I) -> Option<(usize, u64)> {
+        let mut node = TRIE_ROOT;
+        let mut longest = self.nodes[0].weight.map(|weight| (0, weight));
+
+        for (depth, unit) in key.into_iter().enumerate() {
+            match self.child(node, unit) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(weight) = self.nodes[node as usize].weight {
+                longest = Some((depth + 1, weight));
+            }
+        }
+
+        longest
+    }
+
+    pub fn memory_usage(&self) -> TrieMemory {
+        TrieMemory {
+            nodes: self.nodes.len() - self.free.len(),
+            node_bytes: self.nodes.capacity() * mem::size_of::<TrieNode<K>>(),
+            child_bytes: self
+                .nodes
+                .iter()
+                .map(|node| node.children.capacity() * mem::size_of::<(K, u32)>())
+                .sum(),
+        }
+    }
+
+    // Memory for every node in use, in key order with each node before the ones
+    // under it, so sorting by bytes or children shows where the memory goes
+    pub fn node_memory(&self) -> Vec<TrieNodeMemory<K>> {
+        let mut report = Vec::with_capacity(self.nodes.len() - self.free.len());
+        let mut stack = vec![(TRIE_ROOT, Vec::new())];
+
+        while let Some((node, key)) = stack.pop() {
+            let trie_node = &self.nodes[node as usize];
+            for &(unit, child) in trie_node.children.iter().rev() {
+                let mut child_key = key.clone();
+                child_key.push(unit);
+                stack.push((child, child_key));
+            }
+
+            report.push(TrieNodeMemory {
+                key,
+                children: trie_node.children.len(),
+                bytes: mem::size_of::<TrieNode<K>>()
+                    + trie_node.children.capacity() * mem::size_of::<(K, u32)>(),
+            });
+        }
+
+        report
+    }
+}
+
+impl<K: Ord + Copy> Default for Trie<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Heap entry for top_k: either a finished key (node is None) or a subtree still to
+// open up, ranked by weight and then by key, smallest first
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Completion<K> {
+    weight: u64,
+    key: Reverse<Vec<K>>,
+    node: Option<u32>,
+}
+
+pub struct TriePrefixIter<'a, K> {
+    trie: &'a Trie<K>,
+    // nodes still to visit and the key leading to each, the next one on top
+    stack: Vec<(u32, Vec<K>)>,
+}
+
+impl<K: Ord + Copy> Iterator for TriePrefixIter<'_, K> {
+    type Item = (Vec<K>, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, key)) = self.stack.pop() {
+            let trie_node = &self.trie.nodes[node as usize];
+
+            // children go on in reverse so the smallest comes off first
+            for &(unit, child) in trie_node.children.iter().rev() {
+                let mut child_key = key.clone();
+                child_key.push(unit);
+                self.stack.push((child, child_key));
+            }
+
+            if let Some(weight) = trie_node.weight {
+                return Some((key, weight));
+            }
+        }
+
+        None
+    }
+}
+
+// Where a character is in a text: 1-based line and column (counting chars, not
+// bytes) and its 0-based byte offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {} (byte {})",
+            self.line, self.column, self.offset
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelimiterError {
+    // a closer that doesn't match the delimiter that's open
+    Mismatched {
+        open: char,
+        open_at: Position,
+        close: char,
+        close_at: Position,
+    },
+    // a closer with nothing open
+    Unexpected {
+        close: char,
+        at: Position,
+    },
+    // the input ended with this delimiter still open (the innermost one, if several are)
+    Unclosed {
+        open: char,
+        at: Position,
+    },
+    // the input ended inside a quote
+    UnterminatedQuote {
+        quote: char,
+        at: Position,
+    },
+}
+
+impl fmt::Display for DelimiterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DelimiterError::Mismatched {
+                open,
+                open_at,
+                close,
+                close_at,
+            } => write!(
+                f,
+                "'{}' at {} doesn't close '{}' opened at {}",
+                close, close_at, open, open_at
+            ),
+            DelimiterError::Unexpected { close, at } => {
+                write!(f, "'{}' at {} has nothing to close", close, at)
+            }
+            DelimiterError::Unclosed { open, at } => {
+                write!(f, "'{}' opened at {} is never closed", open, at)
+            }
+            DelimiterError::UnterminatedQuote { quote, at } => {
+                write!(f, "quote {} opened at {} is never closed", quote, at)
+            }
+        }
+    }
+}
+
+impl Error for DelimiterError {}
+
+// Checks that delimiters in a text are balanced and properly nested. Characters
+// that aren't delimiters are skipped. Optionally, text between quotes is skipped
+// too, and an escape character makes the character after it count as plain text
+// (inside quotes or out), so "(\")" and "\(" don't open anything.
+pub struct DelimiterChecker {
+    pairs: Vec<(char, char)>,
+    quotes: Vec<char>,
+    escape: Option<char>,
+}
+
+impl DelimiterChecker {
+    // (), [] and {}, with no quotes or escapes
+    pub fn new() -> Self {
+        Self::with_pairs(&BRACKET_PAIRS)
+    }
+
+    // Panics if a pair opens and closes with the same character, since there'd be
+    // no telling which it is; use a quote for those
+    pub fn with_pairs(pairs: &[(char, char)]) -> Self {
+        assert!(
+            pairs.iter().all(|(open, close)| open != close),
+            "a delimiter pair needs different open and close characters"
+        );
+
+        DelimiterChecker {
+            pairs: pairs.to_vec(),
+            quotes: Vec::new(),
+            escape: None,
+        }
+    }
+
+    // Text from one of these to the next of the same character is skipped
+    pub fn quotes(mut self, quotes: &[char]) -> Self {
+        self.quotes = quotes.to_vec();
+        self
+    }
+
+    pub fn escape(mut self, escape: char) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    pub fn check(&self, input: &str) -> Result<(), DelimiterError> {
+        let mut open: Vec<(char, Position)> = Vec::new();
+        let mut quote: Option<(char, Position)> = None;
+        let mut escaped = false;
+        let mut line = 1;
+        let mut column = 0;
+
+        for (offset, c) in input.char_indices() {
+            column += 1;
+            let at = Position {
+                line,
+                column,
+                offset,
+            };
+
+            if escaped {
+                escaped = false;
+            } else if Some(c) == self.escape {
+                escaped = true;
+            } else if let Some((quote_char, _)) = quote {
+                if c == quote_char {
+                    quote = None;
+                }
+            } else if self.quotes.contains(&c) {
+                quote = Some((c, at));
+            } else if self.pairs.iter().any(|&(opener, _)| opener == c) {
+                open.push((c, at));
+            } else if self.pairs.iter().any(|&(_, closer)| closer == c) {
+                match open.pop() {
+                    None => return Err(DelimiterError::Unexpected { close: c, at }),
+                    Some((opener, open_at)) => {
+                        if !self.pairs.contains(&(opener, c)) {
+                            return Err(DelimiterError::Mismatched {
+                                open: opener,
+                                open_at,
+                                close: c,
+                                close_at: at,
+                            });
+                        }
+                    }
+                }
+            }
+
+            if c == '\n' {
+                line += 1;
+                column = 0;
+            }
+        }
+
+        if let Some((quote, at)) = quote {
+            return Err(DelimiterError::UnterminatedQuote { quote, at });
+        }
+        match open.pop() {
+            Some((open, at)) => Err(DelimiterError::Unclosed { open, at }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for DelimiterChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Repairing bracket strings made of (), [] and {}. As with bracket_checker, other
+// characters are left alone and don't affect balance.
+//
+// Every bracket either ends up matched in the repaired string or costs one edit:
+// deleting it, or inserting a partner for it. So the fewest edits either way is
+// the number of brackets minus twice the most pairs a balanced subsequence of them
+// can have. With one kind of bracket a stack finds that greedily, but with several
+// a greedy match can be wrong ("([)]" can keep either pair, but not both), so it's
+// an interval DP over the brackets: O(b^3) time and O(b^2) memory for b brackets.
+// That's fine for the lines and snippets an editor deals with, not for whole files.
+
+const BRACKET_PAIRS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+fn bracket_closer(open: char) -> Option<char> {
+    BRACKET_PAIRS
+        .iter()
+        .find(|&&(opener, _)| opener == open)
+        .map(|&(_, close)| close)
+}
+
+fn bracket_opener(close: char) -> Option<char> {
+    BRACKET_PAIRS
+        .iter()
+        .find(|&&(_, closer)| closer == close)
+        .map(|&(open, _)| open)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BracketRepair {
+    pub edits: usize,
+    pub repaired: String,
+}
+
+// The interval DP shared by the repair functions
+struct BracketMatching<'a> {
+    text: &'a str,
+    // byte offset and char of every bracket in the text
+    brackets: Vec<(usize, char)>,
+    // most_pairs[i * (b + 1) + j] is the most matched pairs a balanced subsequence
+    // of brackets[i..j] can have
+    most_pairs: Vec<u32>,
+}
+
+impl<'a> BracketMatching<'a> {
+    fn new(text: &'a str) -> Self {
+        let brackets: Vec<(usize, char)> = text
+            .char_indices()
+            .filter(|&(_, c)| bracket_closer(c).is_some() || bracket_opener(c).is_some())
+            .collect();
+        let count = brackets.len();
+
+        let mut matching = BracketMatching {
+            text,
+            brackets,
+            most_pairs: vec![0; (count + 1) * (count + 1)],
+        };
+
+        // shorter ranges first, since each range is built from ones inside it
+        for len in 2..=count {
+            for start in 0..=count - len {
+                let end = start + len;
+                let best = matching
+                    .partners(start, end)
+                    .map(|close| matching.pairs_matching(start, close, end))
+                    .fold(matching.pairs(start + 1, end), u32::max);
+                matching.most_pairs[start * (count + 1) + end] = best;
+            }
+        }
+
+        matching
+    }
+
+    fn pairs(&self, start: usize, end: usize) -> u32 {
+        if start >= end {
+            return 0;
+        }
+        self.most_pairs[start * (self.brackets.len() + 1) + end]
+    }
+
+    // Brackets in (start, end) that could close the one at start
+    fn partners(&self, start: usize, end: usize) -> impl Iterator<Item = usize> + '_ {
+        let close = bracket_closer(self.brackets[start].1);
+        (start + 1..end).filter(move |&idx| Some(self.brackets[idx].1) == close)
+    }
+
+    // Pairs in [start, end) if start is matched with close
+    fn pairs_matching(&self, start: usize, close: usize, end: usize) -> u32 {
+        1 + self.pairs(start + 1, close) + self.pairs(close + 1, end)
+    }
+
+    // Which brackets one best solution keeps matched. Walks the DP with a work list
+    // rather than recursion so deep nesting can't overflow the stack
+    fn matched(&self) -> Vec<bool> {
+        let mut keep = vec![false; self.brackets.len()];
+        let mut ranges = vec![(0, self.brackets.len())];
+
+        while let Some((start, end)) = ranges.pop() {
+            if start >= end {
+                continue;
+            }
+
+            let best = self.pairs(start, end);
+            if self.pairs(start + 1, end) == best {
+                ranges.push((start + 1, end));
+                continue;
+            }
+
+            let close = self
+                .partners(start, end)
+                .find(|&close| self.pairs_matching(start, close, end) == best)
+                .unwrap();
+            keep[start] = true;
+            keep[close] = true;
+            ranges.push((start + 1, close));
+            ranges.push((close + 1, end));
+        }
+
+        keep
+    }
+
+    fn min_edits(&self) -> usize {
+        self.brackets.len() - 2 * self.pairs(0, self.brackets.len()) as usize
+    }
+
+    // Text between bracket idx and the next one (or the end of the text)
+    fn gap_after(&self, idx: usize) -> &'a str {
+        let (offset, bracket) = self.brackets[idx];
+        let end = self
+            .brackets
+            .get(idx + 1)
+            .map_or(self.text.len(), |&(next, _)| next);
+        &self.text[offset + bracket.len_utf8()..end]
+    }
+
+    // Every distinct best result for the text from bracket start up to bracket end,
+    // i.e. brackets[start..end] and the gaps after each of them
+    fn variants(
+        &self,
+        start: usize,
+        end: usize,
+        memo: &mut HashMap<(usize, usize), Vec<String>>,
+    ) -> Vec<String> {
+        if start >= end {
+            return vec![String::new()];
+        }
+        if let Some(found) = memo.get(&(start, end)) {
+            return found.clone();
+        }
+
+        let best = self.pairs(start, end);
+        let gap = self.gap_after(start);
+        let mut found = BTreeSet::new();
+
+        // drop the bracket at start
+        if self.pairs(start + 1, end) == best {
+            for rest in self.variants(start + 1, end, memo) {
+                found.insert(format!("{}{}", gap, rest));
+            }
+        }
+
+        // or keep it, matched with each close that still gives a best result
+        let closes: Vec<usize> = self
+            .partners(start, end)
+            .filter(|&close| self.pairs_matching(start, close, end) == best)
+            .collect();
+        for close in closes {
+            let inside = self.variants(start + 1, close, memo);
+            let after = self.variants(close + 1, end, memo);
+            let (open_char, close_char) = (self.brackets[start].1, self.brackets[close].1);
+            let close_gap = self.gap_after(close);
+
+            for inner in &inside {
+                for outer in &after {
+                    found.insert(format!(
+                        "{}{}{}{}{}{}",
+                        open_char, gap, inner, close_char, close_gap, outer
+                    ));
+                }
+            }
+        }
+
+        let found: Vec<String> = found.into_iter().collect();
+        memo.insert((start, end), found.clone());
+        found
+    }
+}
+
+// Balances input by deleting as few brackets as possible
+pub fn repair_by_deletion(input: &str) -> BracketRepair {
+    let matching = BracketMatching::new(input);
+    let keep = matching.matched();
+
+    let mut repaired = String::with_capacity(input.len());
+    let mut bracket = 0;
+    for (offset, c) in input.char_indices() {
+        if matching
+            .brackets
+            .get(bracket)
+            .is_some_and(|&(at, _)| at == offset)
+        {
+            bracket += 1;
+            if !keep[bracket - 1] {
+                continue;
+            }
+        }
+        repaired.push(c);
+    }
+
+    BracketRepair {
+        edits: matching.min_edits(),
+        repaired,
+    }
+}
+
+// Balances input by inserting as few brackets as possible. Each unmatched bracket
+// gets its partner right next to it, which balances it without disturbing anything
+// around it
+pub fn repair_by_insertion(input: &str) -> BracketRepair {
+    let matching = BracketMatching::new(input);
+    let keep = matching.matched();
+
+    let mut repaired = String::with_capacity(input.len() + matching.min_edits());
+    let mut bracket = 0;
+    for (offset, c) in input.char_indices() {
+        let is_bracket = matching
+            .brackets
+            .get(bracket)
+            .is_some_and(|&(at, _)| at == offset);
+        if is_bracket {
+            bracket += 1;
+        }
+
+        if is_bracket && !keep[bracket - 1] {
+            match bracket_closer(c) {
+                Some(close) => {
+                    repaired.push(c);
+                    repaired.push(close);
+                }
+                None => {
+                    repaired.extend(bracket_opener(c));
+                    repaired.push(c);
+                }
+            }
+        } else {
+            repaired.push(c);
+        }
+    }
+
+    BracketRepair {
+        edits: matching.min_edits(),
+        repaired,
+    }
+}
+
+// Byte range of the longest substring that's balanced on its own (the first one,
+// if there's a tie). Other characters count as part of a span, so a string with
+// no brackets at all is entirely valid. One pass with a stack, O(n)
+pub fn longest_valid_span(input: &str) -> Range<usize> {
+    // where the current run of possibly valid text started, and the open brackets in it
+    let mut base = 0;
+    let mut open: Vec<(usize, char)> = Vec::new();
+    let mut best = 0..0;
+
+    for (offset, c) in input.char_indices() {
+        let end = offset + c.len_utf8();
+
+        if bracket_closer(c).is_some() {
+            open.push((offset, c));
+        } else if let Some(opener) = bracket_opener(c) {
+            if open.last().is_some_and(|&(_, top)| top == opener) {
+                open.pop();
+            } else {
+                // nothing valid can reach across an unmatched closer
+                open.clear();
+                base = end;
+                continue;
+            }
+        }
+
+        // the span ending here starts just after the innermost bracket still open
+        let start = open
+            .last()
+            .map_or(base, |&(at, bracket)| at + bracket.len_utf8());
+        if end - start > best.len() {
+            best = start..end;
+        }
+    }
+
+    best
+}
+
+// Every distinct string that can be made balanced by removing the fewest brackets,
+// in sorted order. The count of results can grow exponentially with the input
+// (each of many equivalent choices multiplies them), so this is for short inputs
+pub fn remove_invalid(input: &str) -> Vec<String> {
+    let matching = BracketMatching::new(input);
+    let leading = match matching.brackets.first() {
+        Some(&(offset, _)) => &input[..offset],
+        None => input,
+    };
+
+    let mut memo = HashMap::new();
+    matching
+        .variants(0, matching.brackets.len(), &mut memo)
+        .into_iter()
+        .map(|rest| format!("{}{}", leading, rest))
+        .collect()
+}
+
+// Unicode aware versions of reverse_string, is_palindrome and is_anagram. Those
+// work on chars, but what a reader sees as one character can be several chars: a
+// letter plus combining accents, a flag made of two regional indicators, an emoji
+// joined from others with zero width joiners. These work on extended grapheme
+// clusters instead, so none of that gets pulled apart. And since the same text
+// can be spelled with different chars (é precomposed or as e + U+0301, ﬁ or fi),
+// they can normalize and case fold it first.
+
+// Which Unicode normalization form text is brought into before it's compared.
+// NFC only unifies spellings that are meant to be identical (é vs e + U+0301);
+// NFKC also folds compatibility variants into their plain forms (ﬁ to fi, ① to 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+    #[default]
+    None,
+    Nfc,
+    Nfkc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextOptions {
+    pub normalization: Normalization,
+    // full Unicode case folding, which can change the length of the text (ß
+    // folds to ss). Not the Turkic variant, so İ folds to i + U+0307.
+    // The folding data comes from caseless, which is still on Unicode 16 while
+    // segmentation and normalization are on 17, so any case pairs added in
+    // Unicode 17 aren't folded yet
+    pub case_fold: bool,
+}
+
+fn normalize(input: &str, normalization: Normalization) -> String {
+    match normalization {
+        Normalization::None => input.to_string(),
+        Normalization::Nfc => input.nfc().collect(),
+        Normalization::Nfkc => input.nfkc().collect(),
+    }
+}
+
+// Applies the options to input: normalizes, case folds, then normalizes again,
+// because folding can leave text that isn't normalized any more
+pub fn fold_text(input: &str, options: TextOptions) -> String {
+    let normalized = normalize(input, options.normalization);
+    if !options.case_fold {
+        return normalized;
+    }
+
+    let folded = caseless::default_case_fold_str(&normalized);
+    normalize(&folded, options.normalization)
+}
+
+// Reverses the grapheme clusters of input, after applying options to it
+pub fn reverse_graphemes(input: &str, options: TextOptions) -> String {
+    fold_text(input, options).graphemes(true).rev().collect()
+}
+
+// Whether input reads the same both ways, grapheme by grapheme, after applying
+// options. Like is_palindrome it only looks at letters and digits: graphemes
+// that don't start with an alphanumeric char are skipped
+pub fn is_palindrome_graphemes(input: &str, options: TextOptions) -> bool {
+    let folded = fold_text(input, options);
+    let graphemes: Vec<&str> = folded
+        .graphemes(true)
+        .filter(|grapheme| grapheme.chars().next().is_some_and(char::is_alphanumeric))
+        .collect();
+
+    graphemes.iter().eq(graphemes.iter().rev())
+}
+
+// Whether a and b are made of the same graphemes the same number of times, after
+// applying options to both
+pub fn is_anagram_graphemes(a: &str, b: &str, options: TextOptions) -> bool {
+    let count = |input: &str| {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for grapheme in fold_text(input, options).graphemes(true) {
+            *counts.entry(grapheme.to_string()).or_insert(0) += 1;
+        }
+        counts
+    };
+
+    count(a) == count(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::gen_random_vector;
+
+    #[test]
+    fn test_bracket_checker() {
+        let test1 = String::from("({})");
+        let test2 = String::from("({[]})");
+        let test3 = String::from("(){}[]");
+        let test4 = String::from("([]){}");
+        let test5 = String::from("({)}[]");
+
+        assert!(bracket_checker(test1));
+        assert!(bracket_checker(test2));
+        assert!(bracket_checker(test3));
+        assert!(bracket_checker(test4));
+        assert!(!bracket_checker(test5));
+
+        // unclosed and leading closers used to pass or panic
+        assert!(!bracket_checker(String::from("((")));
+        assert!(!bracket_checker(String::from(")(")));
+        assert!(!bracket_checker(String::from("]")));
+        assert!(bracket_checker(String::from("")));
+        assert!(bracket_checker(String::from("fn main() { let v = [1]; }")));
+    }
+
+    #[test]
+    fn test_string_reversal() {
+        let test1 = String::from("Jello");
+        let test2 = String::from("mellow");
+        let test3 = String::from("purple");
+        let test4 = String::from("");
+        let test5 = String::from(" ");
+
+        assert_eq!(reverse_string(test1), String::from("olleJ"));
+        assert_eq!(reverse_string(test2), String::from("wollem"));
+        assert_eq!(reverse_string(test3), String::from("elprup"));
+        assert_eq!(reverse_string(test4), String::from(""));
+        assert_eq!(reverse_string(test5), String::from(" "));
+    }
+
+    #[test]
+    fn test_number_reversal() {
+        let test1 = 32;
+        let test2 = 42;
+        let test3 = 1;
+        let test4 = -20;
+        let test5 = -30001;
+
+        assert_eq!(reverse_integer(test1), 23);
+        assert_eq!(reverse_integer(test2), 24);
+        assert_eq!(reverse_integer(test3), 1);
+        assert_eq!(reverse_integer(test4), -2);
+        assert_eq!(reverse_integer(test5), -10003);
+
+        // these used to panic
+        assert_eq!(reverse_integer(1534236469), i32::MAX);
+        assert_eq!(reverse_integer(i32::MIN), i32::MIN);
+        assert_eq!(reverse_integer(0), 0);
+        assert_eq!(reverse_integer(1200u64), 21u64);
+    }
+
+    #[test]
+    fn test_reverse_digits() {
+        assert_eq!(checked_reverse_digits(123i32, 10), Some(321));
+        assert_eq!(checked_reverse_digits(-123i32, 10), Some(-321));
+        assert_eq!(checked_reverse_digits(1534236469i32, 10), None);
+        assert_eq!(checked_reverse_digits(1463847412i32, 10), Some(2147483641));
+        assert_eq!(
+            checked_reverse_digits(-1463847412i32, 10),
+            Some(-2147483641)
+        );
+        assert_eq!(checked_reverse_digits(i32::MIN, 10), None);
+        assert_eq!(checked_reverse_digits(i8::MIN, 10), None);
+        assert_eq!(checked_reverse_digits(-28i8, 10), Some(-82));
+        assert_eq!(checked_reverse_digits(0u8, 2), Some(0));
+
+        // other radixes, including the edges of 2 and 36
+        assert_eq!(checked_reverse_digits(0b1011u8, 2), Some(0b1101));
+        assert_eq!(checked_reverse_digits(0b1000_0000u8, 2), Some(1));
+        assert_eq!(checked_reverse_digits(0x12u32, 16), Some(0x21));
+        assert_eq!(
+            checked_reverse_digits(-(36 * 36 + 2) as i64, 36),
+            Some(-(2 * 36 * 36 + 1))
+        );
+        assert_eq!(checked_reverse_digits(i8::MIN, 2), Some(-1));
+        assert_eq!(checked_reverse_digits(u128::MAX, 2), Some(u128::MAX));
+
+        assert_eq!(saturating_reverse_digits(1534236469i32, 10), i32::MAX);
+        assert_eq!(saturating_reverse_digits(-1534236469i32, 10), i32::MIN);
+        assert_eq!(saturating_reverse_digits(199u8, 10), u8::MAX);
+        assert_eq!(saturating_reverse_digits(-45i16, 10), -54);
+
+        // 9646324351 doesn't fit, and wraps around the same way the pushes would
+        assert_eq!(
+            wrapping_reverse_digits(1534236469i32, 10),
+            9646324351i64 as i32
+        );
+        assert_eq!(wrapping_reverse_digits(i32::MIN, 10), -8463847412i64 as i32);
+        assert_eq!(wrapping_reverse_digits(-45i16, 10), -54);
+        assert_eq!(wrapping_reverse_digits(199u8, 10), (991u32 % 256) as u8);
+    }
+
+    #[test]
+    fn test_reverse_digits_matches_strings() {
+        use std::convert::TryFrom;
+
+        for val in gen_random_vector(i32::MIN, i32::MAX, 500) {
+            let digits = val
+                .unsigned_abs()
+                .to_string()
+                .chars()
+                .rev()
+                .collect::<String>();
+            let expected = digits.parse::<i64>().unwrap() * i64::from(val.signum());
+            assert_eq!(
+                checked_reverse_digits(val, 10),
+                i32::try_from(expected).ok()
+            );
+            assert_eq!(wrapping_reverse_digits(val, 10), expected as i32);
+            assert_eq!(checked_reverse_digits(i64::from(val), 10), Some(expected));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reverse_digits_bad_radix() {
+        checked_reverse_digits(1, 1);
+    }
+
+    #[test]
+    fn test_unique_char() {
+        let test1 = String::from("foobar");
+        let test2 = String::from("bazbar");
+        let test3 = String::from("loopzoop");
+        let test4 = String::from("zzzzzz");
+        let test5 = String::from("zzzzzzzooom");
+
+        assert_eq!(first_unique_character(test1), 0);
+        assert_eq!(first_unique_character(test2), 2);
+        assert_eq!(first_unique_character(test3), 0);
+        assert_eq!(first_unique_character(test4), -1);
+        assert_eq!(first_unique_character(test5), 10);
+    }
+
+    #[test]
+    fn test_anagram() {
+        let test1 = (String::from("loop"), String::from("pool"));
+        let test2 = (String::from("zing"), String::from("zang"));
+        let test3 = (String::from("gooloog"), String::from("loogoog"));
+        let test4 = (String::from("al"), String::from("la"));
+        let test5 = (String::from("po"), String::from("p"));
+
+        assert!(is_anagram(test1.0, test1.1));
+        assert!(!is_anagram(test2.0, test2.1));
+        assert!(is_anagram(test3.0, test3.1));
+        assert!(is_anagram(test4.0, test4.1));
+        assert!(!is_anagram(test5.0, test5.1));
+    }
+
+    #[test]
+    fn test_palindrome() {
+        let test1 = String::from("ado://oda");
+        let test2 = String::from("123zing gniz321");
+        let test3 = String::from(":laBodO /a/  odobal:");
+        let test4 = String::from("zoop loop");
+        let test5 = String::from("");
+
+        assert!(is_palindrome(test1));
+        assert!(is_palindrome(test2));
+        assert!(is_palindrome(test3));
+        assert!(!is_palindrome(test4));
+        assert!(is_palindrome(test5));
+    }
+
+    #[test]
+    fn test_longest_substring() {
+        let test1 = String::from("avalanche");
+        let test2 = String::from("");
+        let test3 = String::from("foobar");
+        let test4 = String::from("lollipops");
+        let test5 = String::from("applesauce");
+
+        assert_eq!(longest_substring(test1), Some(String::from("lanche")));
+        assert_eq!(longest_substring(test2), Some(String::from("")));
+        assert_eq!(longest_substring(test3), Some(String::from("obar")));
+        assert_eq!(longest_substring(test4), Some(String::from("lipo")));
+        assert_eq!(longest_substring(test5), Some(String::from("plesauc")));
+    }
+
+    #[test]
+    fn test_string_to_int() {
+        let test1 = String::from("123");
+        let test2 = String::from("-123");
+        let test3 = String::from("-214748364899");
+        let test4 = String::from("2147483648999");
+        let test5 = String::from("1234 halp im stuck in a loop");
+
+        assert_eq!(string_to_int(test1), 123);
+        assert_eq!(string_to_int(test2), -123);
+        assert_eq!(string_to_int(test3), -2147483648);
+        assert_eq!(string_to_int(test4), 2147483647);
+        assert_eq!(string_to_int(test5), 1234);
+
+        // digits past the first non-digit don't count, and no digits is 0
+        assert_eq!(string_to_int(String::from("12a34")), 12);
+        assert_eq!(string_to_int(String::from("a-5")), 0);
+        assert_eq!(string_to_int(String::from("")), 0);
+        assert_eq!(string_to_int(String::from("  +42")), 42);
+    }
+
+    #[test]
+    fn test_parse_int_strict() {
+        use ParseIntError::*;
+        let strict = ParseMode::Strict;
+
+        assert_eq!(parse_int::<i32>("123", 10, strict), Ok(123));
+        assert_eq!(parse_int::<i32>("-123", 10, strict), Ok(-123));
+        assert_eq!(parse_int::<i32>("+123", 10, strict), Ok(123));
+        assert_eq!(parse_int::<i32>("007", 10, strict), Ok(7));
+        assert_eq!(parse_int::<i32>("", 10, strict), Err(Empty));
+        assert_eq!(parse_int::<i32>("-", 10, strict), Err(Empty));
+        assert_eq!(
+            parse_int::<i32>(" 1", 10, strict),
+            Err(InvalidDigit { pos: 0 })
+        );
+        assert_eq!(
+            parse_int::<i32>("12a34", 10, strict),
+            Err(InvalidDigit { pos: 2 })
+        );
+        assert_eq!(
+            parse_int::<i32>("1é", 10, strict),
+            Err(InvalidDigit { pos: 1 })
+        );
+        assert_eq!(
+            parse_int::<i32>("--1", 10, strict),
+            Err(InvalidDigit { pos: 1 })
+        );
+        assert_eq!(
+            parse_int::<u32>("-1", 10, strict),
+            Err(InvalidDigit { pos: 0 })
+        );
+        assert_eq!(
+            parse_int::<u32>("-0", 10, strict),
+            Err(InvalidDigit { pos: 0 })
+        );
+
+        // the edges of every width
+        assert_eq!(parse_int::<i8>("-128", 10, strict), Ok(i8::MIN));
+        assert_eq!(parse_int::<i8>("127", 10, strict), Ok(i8::MAX));
+        assert_eq!(parse_int::<i8>("128", 10, strict), Err(Overflow));
+        assert_eq!(parse_int::<i8>("-129", 10, strict), Err(Overflow));
+        assert_eq!(parse_int::<u8>("255", 10, strict), Ok(u8::MAX));
+        assert_eq!(parse_int::<u8>("256", 10, strict), Err(Overflow));
+        assert_eq!(
+            parse_int::<i128>(&i128::MIN.to_string(), 10, strict),
+            Ok(i128::MIN)
+        );
+        assert_eq!(
+            parse_int::<u128>(&u128::MAX.to_string(), 10, strict),
+            Ok(u128::MAX)
+        );
+        assert_eq!(
+            parse_int::<u128>("340282366920938463463374607431768211456", 10, strict),
+            Err(Overflow)
+        );
+
+        // other radixes, with letters in either case
+        assert_eq!(parse_int::<i32>("ff", 16, strict), Ok(255));
+        assert_eq!(parse_int::<i32>("-FF", 16, strict), Ok(-255));
+        assert_eq!(parse_int::<u8>("11111111", 2, strict), Ok(255));
+        assert_eq!(
+            parse_int::<u8>("102", 2, strict),
+            Err(InvalidDigit { pos: 2 })
+        );
+        assert_eq!(parse_int::<i64>("zz", 36, strict), Ok(35 * 36 + 35));
+        assert_eq!(parse_int::<i8>("-80", 16, strict), Ok(i8::MIN));
+
+        assert_eq!(
+            Overflow.to_string(),
+            "number too large or too small for the type"
+        );
+        assert_eq!(
+            InvalidDigit { pos: 3 }.to_string(),
+            "invalid digit at byte 3"
+        );
+    }
+
+    #[test]
+    fn test_parse_int_atoi() {
+        let atoi = ParseMode::Atoi;
+
+        assert_eq!(parse_int::<i32>("  \t\n\x0B-42abc", 10, atoi), Ok(-42));
+        assert_eq!(parse_int::<i32>("4193 with words", 10, atoi), Ok(4193));
+        assert_eq!(parse_int::<i32>("words and 987", 10, atoi), Ok(0));
+        assert_eq!(parse_int::<i32>("", 10, atoi), Ok(0));
+        assert_eq!(parse_int::<i32>("+-12", 10, atoi), Ok(0));
+        assert_eq!(parse_int::<i32>("- 12", 10, atoi), Ok(0));
+        assert_eq!(parse_int::<i32>("-91283472332", 10, atoi), Ok(i32::MIN));
+        assert_eq!(
+            parse_int::<i32>("91283472332 and more", 10, atoi),
+            Ok(i32::MAX)
+        );
+        assert_eq!(parse_int::<u16>("-5", 10, atoi), Ok(0));
+        assert_eq!(parse_int::<u16>("-0", 10, atoi), Ok(0));
+        assert_eq!(parse_int::<u16>("70000", 10, atoi), Ok(u16::MAX));
+        assert_eq!(parse_int::<i64>(" 7fz", 16, atoi), Ok(0x7f));
+    }
+
+    #[test]
+    fn test_parse_int_matches_std() {
+        for val in gen_random_vector(i32::MIN, i32::MAX, 500) {
+            for radix in [2, 8, 10, 16, 36] {
+                let text = match radix {
+                    10 => val.to_string(),
+                    16 => format!("{}{:x}", if val < 0 { "-" } else { "" }, val.unsigned_abs()),
+                    _ => format!(
+                        "{}{}",
+                        if val < 0 { "-" } else { "" },
+                        to_radix(val.unsigned_abs(), radix)
+                    ),
+                };
+                assert_eq!(
+                    parse_int::<i32>(&text, radix, ParseMode::Strict),
+                    Ok(val),
+                    "{} in base {}",
+                    text,
+                    radix
+                );
+                assert_eq!(
+                    parse_int::<i16>(&text, radix, ParseMode::Strict).ok(),
+                    i16::from_str_radix(&text, radix).ok()
+                );
+                assert_eq!(
+                    parse_int::<u64>(&text, radix, ParseMode::Strict).ok(),
+                    u64::from_str_radix(&text, radix).ok()
+                );
+            }
+        }
+    }
+
+    fn to_radix(mut val: u32, radix: u32) -> String {
+        let mut digits = Vec::new();
+        loop {
+            digits.push(std::char::from_digit(val % radix, radix).unwrap());
+            val /= radix;
+            if val == 0 {
+                break;
+            }
+        }
+        digits.into_iter().rev().collect()
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_parse_int_bad_radix() {
+        let _ = parse_int::<i32>("1", 37, ParseMode::Strict);
+    }
+
+    fn matchers(pattern: &[u8]) -> Vec<Box<dyn Matcher>> {
+        vec![
+            Box::new(Kmp::new(pattern)),
+            Box::new(Horspool::new(pattern)),
+            Box::new(RabinKarp::new(pattern)),
+            Box::new(ZMatcher::new(pattern)),
+        ]
+    }
+
+    // checks every window, taking matches greedily from the left when non-overlapping
+    fn naive_matches(haystack: &[u8], pattern: &[u8], mode: MatchMode) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut start = 0;
+        while start + pattern.len() <= haystack.len() {
+            if haystack[start..start + pattern.len()] == *pattern {
+                found.push(start);
+                start = mode.next_start(start, pattern.len());
+            } else {
+                start += 1;
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn test_matchers() {
+        let haystack = b"abracadabra";
+
+        for matcher in matchers(b"abra") {
+            assert_eq!(matcher.pattern(), b"abra");
+            assert_eq!(
+                matcher
+                    .find_iter(haystack, MatchMode::Overlapping)
+                    .collect::<Vec<_>>(),
+                vec![0, 7]
+            );
+            assert_eq!(matcher.find_first(haystack), Some(0));
+            assert_eq!(matcher.find_first(b"cadabr"), None);
+            assert_eq!(matcher.find_first(b"abr"), None);
+            assert_eq!(matcher.find_first(b""), None);
+        }
+
+        for matcher in matchers(b"aaa") {
+            let found: Vec<_> = matcher
+                .find_iter(b"aaaaa", MatchMode::Overlapping)
+                .collect();
+            assert_eq!(found, vec![0, 1, 2]);
+            let found: Vec<_> = matcher
+                .find_iter(b"aaaaaaa", MatchMode::NonOverlapping)
+                .collect();
+            assert_eq!(found, vec![0, 3]);
+        }
+
+        for matcher in matchers(b"") {
+            let found: Vec<_> = matcher
+                .find_iter(b"abc", MatchMode::NonOverlapping)
+                .collect();
+            assert_eq!(found, vec![0, 1, 2, 3]);
+            assert_eq!(matcher.find_first(b""), Some(0));
+        }
+
+        // byte offsets into a str always land on char boundaries
+        let text = "naïve café, naïve cafés";
+        for matcher in matchers("café".as_bytes()) {
+            let found: Vec<_> = matcher
+                .find_iter(text.as_bytes(), MatchMode::Overlapping)
+                .collect();
+            assert_eq!(
+                found,
+                text.match_indices("café")
+                    .map(|(idx, _)| idx)
+                    .collect::<Vec<_>>()
+            );
+            assert!(found.iter().all(|&idx| text.is_char_boundary(idx)));
+        }
+
+        // every byte value, including ones that would be a separator elsewhere
+        let bytes: Vec<u8> = (0..=255).chain(0..=255).collect();
+        for matcher in matchers(&[255, 0, 1]) {
+            assert_eq!(
+                matcher
+                    .find_iter(&bytes, MatchMode::Overlapping)
+                    .collect::<Vec<_>>(),
+                vec![255]
+            );
+        }
+    }
+
+    #[test]
+    fn test_matchers_match_naive() {
+        // a tiny alphabet so there are plenty of partial and overlapping matches
+        let to_bytes = |values: Vec<i32>| -> Vec<u8> {
+            values.into_iter().map(|val| b'a' + val as u8).collect()
+        };
+
+        for round in 0..300u32 {
+            let haystack = to_bytes(gen_random_vector(0, 3, round % 60));
+            let pattern = to_bytes(gen_random_vector(0, 3, round % 5 + 1));
+
+            for mode in [MatchMode::Overlapping, MatchMode::NonOverlapping] {
+                let expected = naive_matches(&haystack, &pattern, mode);
+                for matcher in matchers(&pattern) {
+                    let found: Vec<_> = matcher.find_iter(&haystack, mode).collect();
+                    assert_eq!(found, expected, "{:?} in {:?}", pattern, haystack);
+                    assert_eq!(matcher.find_first(&haystack), expected.first().copied());
+                }
+            }
+        }
+    }
+
+    // every occurrence of every pattern, in the order MatchKind::All reports them
+    fn naive_all(haystack: &[u8], patterns: &[Vec<u8>]) -> Vec<PatternMatch> {
+        let mut found = Vec::new();
+        for (pattern, bytes) in patterns.iter().enumerate() {
+            if bytes.is_empty() {
+                continue;
+            }
+            for start in naive_matches(haystack, bytes, MatchMode::Overlapping) {
+                found.push(PatternMatch {
+                    pattern,
+                    start,
+                    end: start + bytes.len(),
+                });
+            }
+        }
+        found.sort_by(|a, b| {
+            a.end
+                .cmp(&b.end)
+                .then(a.start.cmp(&b.start))
+                .then(a.pattern.cmp(&b.pattern))
+        });
+        found
+    }
+
+    fn naive_leftmost_longest(haystack: &[u8], patterns: &[Vec<u8>]) -> Vec<PatternMatch> {
+        let all = naive_all(haystack, patterns);
+        let mut found = Vec::new();
+        let mut pos = 0;
+
+        while let Some(&best) = all
+            .iter()
+            .filter(|found| found.start >= pos)
+            .min_by(|a, b| {
+                a.start
+                    .cmp(&b.start)
+                    .then(b.end.cmp(&a.end))
+                    .then(a.pattern.cmp(&b.pattern))
+            })
+        {
+            found.push(best);
+            pos = best.end;
+        }
+        found
+    }
+
+    // hands out a few bytes per read, to split matches across reads
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.step.min(self.bytes.len()).min(buf.len());
+            buf[..len].copy_from_slice(&self.bytes[..len]);
+            self.bytes = &self.bytes[len..];
+            self.step = self.step % 3 + 1;
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_aho_corasick() {
+        let automaton = AhoCorasick::new(["he", "she", "his", "hers"]);
+        assert_eq!(automaton.pattern_count(), 4);
+
+        let spans = |kind| -> Vec<(usize, Range<usize>)> {
+            automaton
+                .find_iter("ushers", kind)
+                .map(|found| (found.pattern, found.span()))
+                .collect()
+        };
+        assert_eq!(spans(MatchKind::All), vec![(1, 1..4), (0, 2..4), (3, 2..6)]);
+        assert_eq!(spans(MatchKind::LeftmostLongest), vec![(1, 1..4)]);
+
+        // a shorter match at the same start loses to a longer one found later
+        let automaton = AhoCorasick::new(["ab", "abcd", "cd", "bcdx"]);
+        let found: Vec<_> = automaton
+            .find_iter("abcdx abcq", MatchKind::LeftmostLongest)
+            .map(|found| found.pattern)
+            .collect();
+        assert_eq!(found, vec![1, 0]);
+        // and a match after the pending one isn't lost while waiting on it
+        let automaton = AhoCorasick::new(["ab", "abcdef", "cd"]);
+        let found: Vec<_> = automaton
+            .find_iter(b"abcdx", MatchKind::LeftmostLongest)
+            .map(|found| found.span())
+            .collect();
+        assert_eq!(found, vec![0..2, 2..4]);
+
+        let automaton = AhoCorasick::new(["", "a"]);
+        assert_eq!(automaton.find_iter("aa", MatchKind::All).count(), 2);
+        assert_eq!(
+            AhoCorasick::new(Vec::<&str>::new())
+                .find_iter("abc", MatchKind::All)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_aho_corasick_case_insensitive() {
+        let keywords = ["ERROR", "warn", "Timeout"];
+        let log = "[warn] retrying... error: TIMEOUT after 30s; Error again";
+
+        let automaton = AhoCorasick::with_ascii_case_insensitive(keywords);
+        let found: Vec<_> = automaton
+            .find_iter(log, MatchKind::LeftmostLongest)
+            .map(|found| (found.pattern, &log[found.span()]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(1, "warn"), (0, "error"), (2, "TIMEOUT"), (0, "Error")]
+        );
+
+        // case sensitive by default, and non-ASCII letters always have to match exactly
+        assert_eq!(
+            AhoCorasick::new(keywords)
+                .find_iter(log, MatchKind::All)
+                .count(),
+            1
+        );
+        let automaton = AhoCorasick::with_ascii_case_insensitive(["Café"]);
+        assert_eq!(
+            automaton
+                .find_iter("CAFÉ café", MatchKind::All)
+                .map(|found| found.start)
+                .collect::<Vec<_>>(),
+            vec![6]
+        );
+    }
+
+    #[test]
+    fn test_aho_corasick_streaming() {
+        let patterns = ["needle", "needles", "dle"];
+        let mut haystack = Vec::new();
+        for idx in 0..3000 {
+            haystack.extend_from_slice(if idx % 7 == 0 { b"needles " } else { b"hay " });
+        }
+        let automaton = AhoCorasick::new(patterns);
+
+        for kind in [MatchKind::All, MatchKind::LeftmostLongest] {
+            let expected: Vec<_> = automaton.find_iter(&haystack, kind).collect();
+
+            // bigger than one read chunk, so matches span reads
+            let streamed: Vec<_> = automaton
+                .stream_find_iter(&haystack[..], kind)
+                .map(Result::unwrap)
+                .collect();
+            assert_eq!(streamed, expected);
+
+            let trickle = Trickle {
+                bytes: &haystack,
+                step: 1,
+            };
+            let streamed: Vec<_> = automaton
+                .stream_find_iter(trickle, kind)
+                .map(Result::unwrap)
+                .collect();
+            assert_eq!(streamed, expected);
+        }
+
+        struct Broken;
+        impl Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk on fire"))
+            }
+        }
+        let mut results = automaton.stream_find_iter(Broken, MatchKind::All);
+        assert!(results.next().unwrap().is_err());
+        assert!(results.next().is_none());
+    }
+
+    #[test]
+    fn test_aho_corasick_matches_naive() {
+        let to_bytes = |values: Vec<i32>| -> Vec<u8> {
+            values.into_iter().map(|val| b'a' + val as u8).collect()
+        };
+
+        for round in 0..300u32 {
+            let haystack = to_bytes(gen_random_vector(0, 3, round % 50));
+            let patterns: Vec<Vec<u8>> = (0..round % 6 + 1)
+                .map(|idx| to_bytes(gen_random_vector(0, 3, (round + idx) % 4 + 1)))
+                .collect();
+            let automaton = AhoCorasick::new(&patterns);
+
+            let mut all: Vec<_> = automaton.find_iter(&haystack, MatchKind::All).collect();
+            // same end is longest first, but the naive version sorts by start there
+            all.sort_by(|a, b| {
+                a.end
+                    .cmp(&b.end)
+                    .then(a.start.cmp(&b.start))
+                    .then(a.pattern.cmp(&b.pattern))
+            });
+            assert_eq!(
+                all,
+                naive_all(&haystack, &patterns),
+                "{:?} in {:?}",
+                patterns,
+                haystack
+            );
+
+            let leftmost: Vec<_> = automaton
+                .find_iter(&haystack, MatchKind::LeftmostLongest)
+                .collect();
+            assert_eq!(
+                leftmost,
+                naive_leftmost_longest(&haystack, &patterns),
+                "{:?} in {:?}",
+                patterns,
+                haystack
+            );
+        }
+    }
+
+    fn small_text(values: Vec<i32>) -> Vec<u8> {
+        values.into_iter().map(|val| b'a' + val as u8).collect()
+    }
+
+    #[test]
+    fn test_suffix_array() {
+        let banana = SuffixArray::new("banana");
+        assert_eq!(banana.suffixes(), &[5, 3, 1, 0, 4, 2]);
+        assert_eq!(banana.lcp(), &[0, 1, 3, 0, 0, 2]);
+        assert_eq!(banana.text(), b"banana");
+
+        assert_eq!(banana.count_occurrences(b"ana"), 2);
+        assert_eq!(banana.occurrences(b"ana"), vec![1, 3]);
+        assert_eq!(banana.count_occurrences(b"banana"), 1);
+        assert_eq!(banana.count_occurrences(b"bananas"), 0);
+        assert_eq!(banana.count_occurrences(b"x"), 0);
+        assert_eq!(banana.count_occurrences(b""), 7);
+        assert_eq!(banana.longest_repeated_substring(), Some(&b"ana"[..]));
+        assert_eq!(banana.distinct_substrings(), 15);
+
+        let empty = SuffixArray::new("");
+        assert!(empty.suffixes().is_empty());
+        assert_eq!(empty.count_occurrences(b"a"), 0);
+        assert_eq!(empty.longest_repeated_substring(), None);
+        assert_eq!(empty.distinct_substrings(), 0);
+        assert_eq!(SuffixArray::new("abc").longest_repeated_substring(), None);
+
+        assert_eq!(longest_common_substring(b"xabcdey", b"zzbcdezz"), b"bcde");
+        assert_eq!(longest_common_substring(b"abc", b"xyz"), b"");
+        assert_eq!(longest_common_substring(b"", b"xyz"), b"");
+        assert_eq!(longest_common_substring(b"same", b"same"), b"same");
+        // bytes at the top of the range don't get confused with the separator
+        assert_eq!(
+            longest_common_substring(&[255, 255, 1], &[0, 255, 255]),
+            &[255, 255]
+        );
+    }
+
+    #[test]
+    fn test_suffix_array_matches_naive() {
+        for round in 0..200u32 {
+            let text = small_text(gen_random_vector(0, (round % 4 + 1) as i32, round % 40));
+            let array = SuffixArray::new(&text);
+
+            let mut expected: Vec<u32> = (0..text.len() as u32).collect();
+            expected.sort_by_key(|&start| &text[start as usize..]);
+            assert_eq!(array.suffixes(), &expected[..], "{:?}", text);
+
+            for idx in 1..text.len() {
+                let (prev, curr) = (
+                    &text[expected[idx - 1] as usize..],
+                    &text[expected[idx] as usize..],
+                );
+                let common = prev.iter().zip(curr).take_while(|(a, b)| a == b).count();
+                assert_eq!(array.lcp()[idx] as usize, common);
+            }
+
+            let mut distinct = HashSet::new();
+            let mut longest_repeat = 0;
+            for start in 0..text.len() {
+                for end in start + 1..=text.len() {
+                    let sub = &text[start..end];
+                    if !distinct.insert(sub) {
+                        longest_repeat = longest_repeat.max(sub.len());
+                    }
+                    assert_eq!(
+                        array.count_occurrences(sub),
+                        naive_matches(&text, sub, MatchMode::Overlapping).len()
+                    );
+                }
+            }
+            assert_eq!(array.distinct_substrings(), distinct.len() as u64);
+            assert_eq!(
+                array.longest_repeated_substring().map_or(0, <[u8]>::len),
+                longest_repeat
+            );
+            if let Some(repeat) = array.longest_repeated_substring() {
+                assert!(array.count_occurrences(repeat) >= 2);
+            }
+
+            let other = small_text(gen_random_vector(0, 3, round % 25));
+            let common = longest_common_substring(&text, &other);
+            let mut expected_len = 0;
+            for start in 0..text.len() {
+                for end in start + 1..=text.len() {
+                    if Kmp::new(&text[start..end]).find_first(&other).is_some() {
+                        expected_len = expected_len.max(end - start);
+                    }
+                }
+            }
+            assert_eq!(common.len(), expected_len, "{:?} and {:?}", text, other);
+            assert!(common.is_empty() || Kmp::new(common).find_first(&other).is_some());
+        }
+    }
+
+    #[test]
+    fn test_suffix_array_large() {
+        // one repeated byte is the worst case for naive suffix sorting
+        let same = vec![b'a'; 200_000];
+        let array = SuffixArray::new(&same);
+        assert_eq!(array.suffixes()[0], 199_999);
+        assert_eq!(
+            array.longest_repeated_substring().map(<[u8]>::len),
+            Some(199_999)
+        );
+        assert_eq!(array.distinct_substrings(), 200_000);
+
+        let text = small_text(gen_random_vector(0, 4, 300_000));
+        let array = SuffixArray::new(&text);
+        let sorted = array
+            .suffixes()
+            .windows(2)
+            .all(|pair| text[pair[0] as usize..] < text[pair[1] as usize..]);
+        assert!(sorted);
+        assert_eq!(
+            array.count_occurrences(&text[1000..1010]),
+            naive_matches(&text, &text[1000..1010], MatchMode::Overlapping).len()
+        );
+    }
+
+    fn to_string(key: Vec<char>) -> String {
+        key.into_iter().collect()
+    }
+
+    #[test]
+    fn test_trie() {
+        let mut trie = Trie::new();
+        assert!(trie.is_empty());
+        assert!(!trie.starts_with("".chars()));
+
+        for (word, weight) in [
+            ("car", 5),
+            ("cart", 2),
+            ("carbon", 9),
+            ("cat", 7),
+            ("dog", 1),
+        ] {
+            assert_eq!(trie.insert(word.chars(), weight), None);
+        }
+        assert_eq!(trie.insert("car".chars(), 6), Some(5));
+        assert_eq!(trie.len(), 5);
+
+        assert!(trie.contains("car".chars()));
+        assert!(!trie.contains("ca".chars()));
+        assert!(!trie.contains("cars".chars()));
+        assert_eq!(trie.weight("cat".chars()), Some(7));
+        assert!(trie.starts_with("ca".chars()));
+        assert!(trie.starts_with("".chars()));
+        assert!(!trie.starts_with("cb".chars()));
+
+        let keys: Vec<(String, u64)> = trie
+            .keys_with_prefix("car".chars())
+            .map(|(key, weight)| (to_string(key), weight))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("car".to_string(), 6),
+                ("carbon".to_string(), 9),
+                ("cart".to_string(), 2)
+            ]
+        );
+        assert_eq!(trie.keys_with_prefix("x".chars()).count(), 0);
+        assert_eq!(trie.keys_with_prefix("".chars()).count(), 5);
+
+        let top: Vec<(String, u64)> = trie
+            .top_k("ca".chars(), 3)
+            .into_iter()
+            .map(|(key, weight)| (to_string(key), weight))
+            .collect();
+        assert_eq!(
+            top,
+            vec![
+                ("carbon".to_string(), 9),
+                ("cat".to_string(), 7),
+                ("car".to_string(), 6)
+            ]
+        );
+        assert_eq!(trie.top_k("".chars(), 100).len(), 5);
+        assert!(trie.top_k("ca".chars(), 0).is_empty());
+        assert!(trie.top_k("z".chars(), 3).is_empty());
+
+        assert_eq!(trie.remove("carbon".chars()), Some(9));
+        assert_eq!(trie.remove("carbon".chars()), None);
+        assert_eq!(trie.remove("ca".chars()), None);
+        assert_eq!(
+            trie.top_k("ca".chars(), 1),
+            vec![("cat".chars().collect(), 7)]
+        );
+        assert!(!trie.starts_with("carb".chars()));
+        assert_eq!(trie.len(), 4);
+    }
+
+    #[test]
+    fn test_trie_longest_prefix_match() {
+        // routes keyed on address bits, weight is the next hop
+        let bits = |route: &str| route.bytes().map(|bit| bit - b'0').collect::<Vec<u8>>();
+        let mut routes = Trie::new();
+        routes.insert(bits(""), 0);
+        routes.insert(bits("10"), 1);
+        routes.insert(bits("1011"), 2);
+        routes.insert(bits("110"), 3);
+
+        assert_eq!(routes.longest_prefix_match(bits("10110000")), Some((4, 2)));
+        assert_eq!(routes.longest_prefix_match(bits("10100000")), Some((2, 1)));
+        assert_eq!(routes.longest_prefix_match(bits("11000000")), Some((3, 3)));
+        assert_eq!(routes.longest_prefix_match(bits("01")), Some((0, 0)));
+
+        routes.remove(bits(""));
+        assert_eq!(routes.longest_prefix_match(bits("01")), None);
+        assert_eq!(routes.longest_prefix_match(b"ab".iter().copied()), None);
+    }
+
+    #[test]
+    fn test_trie_memory() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.memory_usage().nodes, 1);
+
+        let words = ["tea", "ten", "ted", "to", "inn"];
+        for word in words {
+            trie.insert(word.bytes(), 1);
+        }
+        // the root plus one node per distinct prefix
+        let memory = trie.memory_usage();
+        assert_eq!(memory.nodes, 10);
+        assert!(memory.child_bytes >= 9 * mem::size_of::<(u8, u32)>());
+        assert!(memory.node_bytes >= 10 * mem::size_of::<TrieNode<u8>>());
+        assert_eq!(memory.total_bytes(), memory.node_bytes + memory.child_bytes);
+        assert!(memory.average_node_bytes() >= mem::size_of::<TrieNode<u8>>() as f64);
+
+        // per node: the root and "te" fan out, everything else has one child or none
+        let nodes = trie.memory_usage().nodes;
+        let report = trie.node_memory();
+        assert_eq!(report.len(), nodes);
+        assert_eq!(report[0].key, b"");
+        assert_eq!(report[0].children, 2);
+        let keys: Vec<&[u8]> = report.iter().map(|node| &node.key[..]).collect();
+        assert_eq!(
+            keys,
+            vec![
+                &b""[..],
+                b"i",
+                b"in",
+                b"inn",
+                b"t",
+                b"te",
+                b"tea",
+                b"ted",
+                b"ten",
+                b"to"
+            ]
+        );
+        let te = report.iter().find(|node| node.key == b"te").unwrap();
+        assert_eq!(te.children, 3);
+        assert!(te.bytes >= mem::size_of::<TrieNode<u8>>() + 3 * mem::size_of::<(u8, u32)>());
+        assert!(report
+            .iter()
+            .filter(|node| node.children == 0)
+            .all(|node| node.bytes == mem::size_of::<TrieNode<u8>>()));
+        // with no free slots the nodes add up to the totals
+        assert_eq!(
+            report.iter().map(|node| node.bytes).sum::<usize>(),
+            nodes * mem::size_of::<TrieNode<u8>>() + memory.child_bytes
+        );
+
+        // removing prunes everything but the root, and the freed slots get reused
+        for word in words {
+            trie.remove(word.bytes());
+        }
+        assert_eq!(trie.memory_usage().nodes, 1);
+        let slots = trie.nodes.len();
+        trie.insert("tent".bytes(), 1);
+        assert_eq!(trie.nodes.len(), slots);
+    }
+
+    #[test]
+    fn test_trie_matches_btreemap() {
+        let mut trie = Trie::new();
+        let mut expected: BTreeMap<Vec<u8>, u64> = BTreeMap::new();
+
+        for round in 0..2000u32 {
+            let key = small_text(gen_random_vector(0, 3, round % 5));
+            let weight = gen_random_vector(0, 50, 1)[0] as u64;
+
+            if round % 3 == 0 {
+                assert_eq!(trie.remove(key.iter().copied()), expected.remove(&key));
+            } else {
+                assert_eq!(
+                    trie.insert(key.iter().copied(), weight),
+                    expected.insert(key.clone(), weight)
+                );
+            }
+            assert_eq!(trie.len(), expected.len());
+
+            let prefix = &key[..key.len() / 2];
+            let with_prefix: Vec<(Vec<u8>, u64)> = expected
+                .iter()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .map(|(key, &weight)| (key.clone(), weight))
+                .collect();
+            assert_eq!(
+                trie.keys_with_prefix(prefix.iter().copied())
+                    .collect::<Vec<_>>(),
+                with_prefix
+            );
+            assert_eq!(
+                trie.starts_with(prefix.iter().copied()),
+                !with_prefix.is_empty()
+            );
+
+            let mut ranked = with_prefix.clone();
+            ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            ranked.truncate(4);
+            assert_eq!(trie.top_k(prefix.iter().copied(), 4), ranked);
+
+            let longest = (0..=key.len())
+                .rev()
+                .find_map(|len| expected.get(&key[..len]).map(|&weight| (len, weight)));
+            assert_eq!(trie.longest_prefix_match(key.iter().copied()), longest);
+
+            // one node per distinct prefix of the stored keys, plus the root
+            let prefixes: HashSet<&[u8]> = expected
+                .keys()
+                .flat_map(|key| (1..=key.len()).map(move |len| &key[..len]))
+                .collect();
+            assert_eq!(trie.memory_usage().nodes, prefixes.len() + 1);
+        }
+    }
+
+    fn at(line: usize, column: usize, offset: usize) -> Position {
+        Position {
+            line,
+            column,
+            offset,
+        }
+    }
+
+    #[test]
+    fn test_delimiter_checker() {
+        let checker = DelimiterChecker::new();
+        assert_eq!(checker.check("a(b[c]{d})e"), Ok(()));
+        assert_eq!(checker.check(""), Ok(()));
+
+        assert_eq!(
+            checker.check("(]"),
+            Err(DelimiterError::Mismatched {
+                open: '(',
+                open_at: at(1, 1, 0),
+                close: ']',
+                close_at: at(1, 2, 1)
+            })
+        );
+        assert_eq!(
+            checker.check("x)"),
+            Err(DelimiterError::Unexpected {
+                close: ')',
+                at: at(1, 2, 1)
+            })
+        );
+        assert_eq!(
+            checker.check("{\n  (\n"),
+            Err(DelimiterError::Unclosed {
+                open: '(',
+                at: at(2, 3, 4)
+            })
+        );
+
+        // columns count chars, offsets count bytes
+        let err = checker.check("é\nçà]").unwrap_err();
+        assert_eq!(
+            err,
+            DelimiterError::Unexpected {
+                close: ']',
+                at: at(2, 3, 7)
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "']' at line 2, column 3 (byte 7) has nothing to close"
+        );
+
+        // quotes and escapes are off by default
+        assert!(checker.check("\"(\"").is_err());
+        assert!(checker.check("\\(").is_err());
+    }
+
+    #[test]
+    fn test_delimiter_checker_options() {
+        let checker = DelimiterChecker::new().quotes(&['"', '\'']).escape('\\');
+        assert_eq!(checker.check("f(\"(\", ')')"), Ok(()));
+        assert_eq!(checker.check("f(\"\\\")\")"), Ok(()));
+        assert_eq!(checker.check("\\(x"), Ok(()));
+        assert_eq!(checker.check("s = \"it's\""), Ok(()));
+        assert_eq!(
+            checker.check("f(\"oops)"),
+            Err(DelimiterError::UnterminatedQuote {
+                quote: '"',
+                at: at(1, 3, 2)
+            })
+        );
+
+        let tags = DelimiterChecker::with_pairs(&[('<', '>'), ('«', '»')]);
+        assert_eq!(tags.check("<a «b» (c>"), Ok(()));
+        assert_eq!(
+            tags.check("«<»").unwrap_err(),
+            DelimiterError::Mismatched {
+                open: '<',
+                open_at: at(1, 2, 2),
+                close: '»',
+                close_at: at(1, 3, 3)
+            }
+        );
+        assert!(tags
+            .check("<«>»")
+            .unwrap_err()
+            .to_string()
+            .contains("doesn't close '«'"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_delimiter_checker_same_open_close() {
+        DelimiterChecker::with_pairs(&[('|', '|')]);
+    }
+
+    // random strings of brackets with the odd letter mixed in
+    fn bracket_soup(count: u32) -> String {
+        gen_random_vector(0, 7, count)
+            .into_iter()
+            .map(|val| "()[]{}a".as_bytes()[val as usize] as char)
+            .collect()
+    }
+
+    fn is_balanced(input: &str) -> bool {
+        DelimiterChecker::new().check(input).is_ok()
+    }
+
+    // tries every way of dropping brackets, fewest first
+    fn brute_force_removals(input: &str) -> (usize, Vec<String>) {
+        let chars: Vec<char> = input.chars().collect();
+        let brackets: Vec<usize> = (0..chars.len()).filter(|&idx| chars[idx] != 'a').collect();
+
+        for removed in 0..=brackets.len() {
+            let mut found = BTreeSet::new();
+            for mask in 0u32..1 << brackets.len() {
+                if mask.count_ones() as usize != removed {
+                    continue;
+                }
+                let dropped: HashSet<usize> = (0..brackets.len())
+                    .filter(|bit| mask & (1 << bit) != 0)
+                    .map(|bit| brackets[bit])
+                    .collect();
+                let kept: String = (0..chars.len())
+                    .filter(|idx| !dropped.contains(idx))
+                    .map(|idx| chars[idx])
+                    .collect();
+                if is_balanced(&kept) {
+                    found.insert(kept);
+                }
+            }
+            if !found.is_empty() {
+                return (removed, found.into_iter().collect());
+            }
+        }
+        unreachable!("removing every bracket always balances")
+    }
+
+    #[test]
+    fn test_bracket_repair() {
+        assert_eq!(remove_invalid("()())()"), vec!["(())()", "()()()"]);
+        assert_eq!(remove_invalid("(a)())()"), vec!["(a())()", "(a)()()"]);
+        assert_eq!(remove_invalid(")("), vec![""]);
+        assert_eq!(remove_invalid("x"), vec!["x"]);
+        assert_eq!(remove_invalid(""), vec![""]);
+        assert_eq!(remove_invalid("([)]"), vec!["()", "[]"]);
+
+        let fixed = repair_by_deletion("f(a[0)]");
+        assert_eq!(fixed.edits, 2);
+        assert!(is_balanced(&fixed.repaired));
+        assert_eq!(
+            repair_by_deletion("{ok}"),
+            BracketRepair {
+                edits: 0,
+                repaired: "{ok}".to_string()
+            }
+        );
+
+        assert_eq!(
+            repair_by_insertion("(("),
+            BracketRepair {
+                edits: 2,
+                repaired: "()()".to_string()
+            }
+        );
+        assert_eq!(
+            repair_by_insertion("a]b"),
+            BracketRepair {
+                edits: 1,
+                repaired: "a[]b".to_string()
+            }
+        );
+        assert_eq!(repair_by_insertion("([)]").edits, 2);
+
+        assert_eq!(longest_valid_span(")()())"), 1..5);
+        assert_eq!(longest_valid_span("(()"), 1..3);
+        assert_eq!(longest_valid_span("{[}]()"), 4..6);
+        assert_eq!(longest_valid_span("no brackets"), 0..11);
+        assert_eq!(longest_valid_span(""), 0..0);
+        assert_eq!(longest_valid_span("(((("), 0..0);
+        let text = "é)(ü[ö])";
+        assert_eq!(&text[longest_valid_span(text)], "(ü[ö])");
+    }
+
+    #[test]
+    fn test_bracket_repair_matches_brute_force() {
+        for round in 0..300u32 {
+            let input = bracket_soup(round % 12);
+            let (fewest, expected) = brute_force_removals(&input);
+
+            assert_eq!(remove_invalid(&input), expected, "{}", input);
+
+            let deleted = repair_by_deletion(&input);
+            assert_eq!(deleted.edits, fewest);
+            assert!(expected.contains(&deleted.repaired), "{}", input);
+
+            let inserted = repair_by_insertion(&input);
+            assert_eq!(inserted.edits, fewest);
+            assert!(is_balanced(&inserted.repaired), "{}", input);
+            assert_eq!(inserted.repaired.len(), input.len() + fewest);
+
+            // longest balanced substring, earliest on a tie
+            let mut longest = 0..0;
+            for start in 0..=input.len() {
+                for end in start..=input.len() {
+                    if end - start > longest.len() && is_balanced(&input[start..end]) {
+                        longest = start..end;
+                    }
+                }
+            }
+            assert_eq!(longest_valid_span(&input), longest, "{}", input);
+        }
+    }
+
+    // Unmodified files from the UCD, each in the Unicode version the crate it
+    // checks is built from: 17.0.0 for unicode-segmentation and
+    // unicode-normalization, 16.0.0 for caseless. See testdata/LICENSE-UNICODE
+    const GRAPHEME_BREAK_TEST: &str = include_str!("../testdata/GraphemeBreakTest.txt");
+    const NORMALIZATION_TEST: &str = include_str!("../testdata/NormalizationTest.txt");
+    const CASE_FOLDING: &str = include_str!("../testdata/CaseFolding.txt");
+
+    // "0044 0307" -> "D\u{307}"
+    fn from_code_points(hex: &str) -> String {
+        hex.split_whitespace()
+            .map(|code| char::from_u32(u32::from_str_radix(code, 16).unwrap()).unwrap())
+            .collect()
+    }
+
+    // the data lines of a UCD file, without comments or NormalizationTest's
+    // @Part headers
+    fn data_lines(file: &str) -> impl Iterator<Item = &str> {
+        file.lines()
+            .map(|line| line.split('#').next().unwrap().trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('@'))
+    }
+
+    const NFC: TextOptions = TextOptions {
+        normalization: Normalization::Nfc,
+        case_fold: false,
+    };
+    const NFKC: TextOptions = TextOptions {
+        normalization: Normalization::Nfkc,
+        case_fold: false,
+    };
+    const FOLD: TextOptions = TextOptions {
+        normalization: Normalization::None,
+        case_fold: true,
+    };
+
+    #[test]
+    fn test_grapheme_break_data() {
+        let mut cases = 0;
+        for line in data_lines(GRAPHEME_BREAK_TEST) {
+            // clusters are separated by ÷, code points within one by ×
+            let clusters: Vec<String> = line
+                .split('÷')
+                .map(|cluster| from_code_points(&cluster.replace('×', " ")))
+                .filter(|cluster| !cluster.is_empty())
+                .collect();
+            let input: String = clusters.concat();
+            let reversed: String = clusters.iter().rev().map(String::as_str).collect();
+            let plain = TextOptions::default();
+
+            assert_eq!(
+                input.graphemes(true).collect::<Vec<_>>(),
+                clusters,
+                "{}",
+                line
+            );
+            assert_eq!(reverse_graphemes(&input, plain), reversed, "{}", line);
+
+            // reversed text can segment differently where clusters meet (a lone
+            // mark attaches to whatever now comes before it), so for the anagram
+            // and palindrome checks the clusters are kept apart by a control char,
+            // which nothing joins across. The controls aren't alphanumeric, so the
+            // palindrome check skips them
+            let separated = |clusters: Vec<&String>| {
+                clusters
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join("\u{1}")
+            };
+            let forwards = separated(clusters.iter().collect());
+            let backwards = separated(clusters.iter().rev().collect());
+            assert!(
+                is_anagram_graphemes(&forwards, &backwards, plain),
+                "{}",
+                line
+            );
+            let mirrored = separated(clusters.iter().chain(clusters.iter().rev()).collect());
+            assert!(is_palindrome_graphemes(&mirrored, plain), "{}", line);
+            cases += 1;
+        }
+        assert_eq!(cases, 766);
+    }
+
+    #[test]
+    fn test_normalization_data() {
+        let mut cases = 0;
+        for line in data_lines(NORMALIZATION_TEST) {
+            let columns: Vec<String> = line.split(';').take(5).map(from_code_points).collect();
+            let (source, nfc, nfd, nfkc, nfkd) = (
+                &columns[0],
+                &columns[1],
+                &columns[2],
+                &columns[3],
+                &columns[4],
+            );
+
+            // the conformance rules: NFC of the source, NFC and NFD columns is the
+            // NFC column, and NFKC of every column is the NFKC column
+            for column in [source, nfc, nfd] {
+                assert_eq!(&fold_text(column, NFC), nfc, "{}", line);
+            }
+            for column in [source, nfc, nfd, nfkc, nfkd] {
+                assert_eq!(&fold_text(column, NFKC), nfkc, "{}", line);
+            }
+
+            // so differently spelled versions of the same text compare equal
+            assert!(is_anagram_graphemes(source, nfd, NFC), "{}", line);
+            assert!(is_anagram_graphemes(source, nfkd, NFKC), "{}", line);
+            assert_eq!(
+                reverse_graphemes(nfd, NFC),
+                reverse_graphemes(source, NFC),
+                "{}",
+                line
+            );
+            cases += 1;
+        }
+        assert!(cases > 19_000);
+    }
+
+    #[test]
+    fn test_case_folding_data() {
+        let mut cases = 0;
+        for line in data_lines(CASE_FOLDING) {
+            let columns: Vec<&str> = line.split(';').map(str::trim).collect();
+            // full folding is the common and full mappings
+            if columns[1] != "C" && columns[1] != "F" {
+                continue;
+            }
+
+            let (code, folded) = (from_code_points(columns[0]), from_code_points(columns[2]));
+            assert_eq!(fold_text(&code, FOLD), folded, "{}", line);
+            cases += 1;
+        }
+        assert!(cases > 0);
+    }
+
+    #[test]
+    fn test_fold_text() {
+        // canonical equivalents come out the same, with marks in canonical order
+        assert_eq!(fold_text("\u{1e0a}\u{323}", NFC), "\u{1e0c}\u{307}");
+        assert_eq!(fold_text("D\u{307}\u{323}", NFC), "\u{1e0c}\u{307}");
+        assert_eq!(fold_text("\u{212b}", NFC), "\u{c5}");
+        assert_eq!(fold_text("\u{1100}\u{1161}\u{11a8}", NFC), "\u{ac01}");
+        // compatibility characters only change under NFKC
+        assert_eq!(fold_text("\u{fb01}", NFC), "\u{fb01}");
+        assert_eq!(fold_text("\u{fb01}", NFKC), "fi");
+        assert_eq!(fold_text("x\u{b2}", NFKC), "x2");
+
+        // folding can decompose, so it's normalized again afterwards
+        let fold_nfc = TextOptions {
+            normalization: Normalization::Nfc,
+            case_fold: true,
+        };
+        assert_eq!(fold_text("\u{130}", FOLD), "i\u{307}");
+        assert_eq!(fold_text("\u{1e9e}", fold_nfc), "ss");
+        assert_eq!(fold_text("\u{c9}COLE", fold_nfc), "\u{e9}cole");
+        assert_eq!(fold_text("", fold_nfc), "");
+    }
+
+    #[test]
+    fn test_grapheme_string_ops() {
+        // the accent stays on the e instead of moving onto the a
+        let decomposed = "e\u{301}a";
+        assert_eq!(reverse_string(decomposed.to_string()), "a\u{301}e");
+        assert_eq!(
+            reverse_graphemes(decomposed, TextOptions::default()),
+            "ae\u{301}"
+        );
+        assert_eq!(reverse_graphemes(decomposed, NFC), "a\u{e9}");
+        assert_eq!(reverse_graphemes("🇫🇷🇩🇪", TextOptions::default()), "🇩🇪🇫🇷");
+        assert_eq!(reverse_graphemes("ab👩‍👩‍👧", TextOptions::default()), "👩‍👩‍👧ba");
+        assert_eq!(reverse_graphemes("", NFKC), "");
+
+        // "été" with the first é precomposed and the last one not
+        let mixed = "\u{e9}t\u{65}\u{301}";
+        assert!(!is_palindrome_graphemes(mixed, TextOptions::default()));
+        assert!(is_palindrome_graphemes(mixed, NFC));
+        assert!(is_palindrome_graphemes("Never odd or even", FOLD));
+        assert!(!is_palindrome_graphemes(
+            "Never odd or even",
+            TextOptions::default()
+        ));
+        // folding doesn't strip accents, so É and e still differ
+        assert!(!is_palindrome_graphemes(
+            "Ésope reste ici et se repose",
+            FOLD
+        ));
+        assert!(is_palindrome_graphemes("", TextOptions::default()));
+
+        assert!(is_anagram_graphemes("Straße", "strasse", FOLD));
+        assert!(!is_anagram_graphemes(
+            "Straße",
+            "strasse",
+            TextOptions::default()
+        ));
+        assert!(is_anagram_graphemes("ﬁre", "rife", NFKC));
+        assert!(!is_anagram_graphemes("ﬁre", "rife", NFC));
+        // é is one grapheme however it's spelled, but only equal after normalizing
+        assert!(is_anagram_graphemes("caf\u{e9}", "\u{65}\u{301}fac", NFC));
+        assert!(!is_anagram_graphemes(
+            "caf\u{e9}",
+            "\u{65}\u{301}fac",
+            TextOptions::default()
+        ));
+        assert!(!is_anagram_graphemes("ab", "abb", TextOptions::default()));
     }
 }
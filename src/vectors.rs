@@ -11,7 +11,7 @@ pub fn remove_dupes(mut target: Vec<i32>) -> Vec<i32> {
     let mut i = 1;
 
     while i < target.len() {
-        if &target[i] == &target[i - 1] {
+        if target[i] == target[i - 1] {
             // remove the current index if it is == value at prev index
             target.remove(i);
             // continue for another iteration without incrementing i
@@ -20,7 +20,7 @@ pub fn remove_dupes(mut target: Vec<i32>) -> Vec<i32> {
         }
         // if it the current and previous values are not equal,
         // step into the next vector position
-        i = i + 1;
+        i += 1;
     }
     target
 }
@@ -34,8 +34,8 @@ pub fn max_return(prices: Vec<i32>) -> i32 {
         // if the price from the day before is less than the current price
         // we can add it to max_profit. Will eventually return
         // the max profit from the input array
-        if &prices[i] > &prices[i - 1] {
-            max_profit = max_profit + (prices[i] - prices[i - 1]);
+        if prices[i] > prices[i - 1] {
+            max_profit += prices[i] - prices[i - 1];
         }
     }
 
@@ -137,7 +137,7 @@ pub fn add_one(mut input: Vec<u32>) -> Vec<u32> {
     plus_one
         .to_string()
         .chars()
-        .map(|c| c.to_digit(10).unwrap() as u32)
+        .map(|c| c.to_digit(10).unwrap())
         .collect()
 }
 
@@ -191,8 +191,8 @@ pub fn add_to_target_mapped(input: Vec<i32>, target: i32) -> Vec<(usize, usize)>
         hashed.insert(*t, i);
     }
 
-    for i in 0..input.len() {
-        let temp = &target - input[i];
+    for (i, val) in input.iter().enumerate() {
+        let temp = target - val;
         let index = match hashed.get(&temp) {
             Some(t) => t,
             None => continue,
@@ -235,9 +235,9 @@ pub fn is_valid_sudoku(input: Vec<Vec<&str>>) -> bool {
     fn test_square(input: &[Vec<&str>], start: usize, window: usize) -> bool {
         let mut flat: Vec<&str> = Vec::new();
 
-        for i in start..window {
-            for j in start..window {
-                flat.push(input[i][j]);
+        for row in &input[start..window] {
+            for cell in &row[start..window] {
+                flat.push(cell);
             }
         }
         unroll_data(&flat[..]) // return true if the square is valid
@@ -246,16 +246,16 @@ pub fn is_valid_sudoku(input: Vec<Vec<&str>>) -> bool {
     fn test_column(input: &[Vec<&str>], col: usize) -> bool {
         let mut flat: Vec<&str> = Vec::new();
         // for each row in the input matrix
-        for i in 0..input.len() {
-            flat.push(input[i][col]);
+        for row in input {
+            flat.push(row[col]);
         }
 
         unroll_data(&flat[..])
     }
 
     // test the rows
-    for row in 0..input.len() {
-        match unroll_data(&input[row]) {
+    for row in &input {
+        match unroll_data(row) {
             true => continue,
             false => return false,
         }
@@ -275,7 +275,7 @@ pub fn is_valid_sudoku(input: Vec<Vec<&str>>) -> bool {
     let mut start = 0;
 
     while window < input.len() {
-        if test_square(&input, start, window) == false {
+        if !test_square(&input, start, window) {
             return false;
         }
 